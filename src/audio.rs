@@ -0,0 +1,267 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use rodio::{
+    ChannelCount, DeviceTrait, OutputStream, OutputStreamBuilder, Sample, SampleRate, Source,
+    cpal::{self, traits::HostTrait},
+    source::SeekError,
+};
+
+use crate::AnyError;
+
+pub fn output_device_names() -> Vec<String> {
+    cpal::default_host()
+        .output_devices()
+        .map(|devices| devices.filter_map(|device| device.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Opens `device_name`, or the system default when it is `None`. Returns
+/// whether the named device was found so callers can report the fallback.
+pub fn open_output_stream(device_name: Option<&str>) -> Result<(OutputStream, bool), AnyError> {
+    let device = device_name.and_then(|device_name| {
+        cpal::default_host()
+            .output_devices()
+            .ok()?
+            .find(|device| device.name().is_ok_and(|name| name == device_name))
+    });
+    let is_found = device_name.is_none() || device.is_some();
+
+    let mut stream = match device {
+        Some(device) => OutputStreamBuilder::from_device(device)?.open_stream_or_fallback()?,
+        None => OutputStreamBuilder::open_default_stream()?,
+    };
+    stream.log_on_drop(false);
+    Ok((stream, is_found))
+}
+
+/// Shared between the player and the audio thread, so skipping can be toggled
+/// mid-episode and the skipped time added back to the sink's position.
+#[derive(Default)]
+pub struct SilenceSkipper {
+    enabled: AtomicBool,
+    skipped_nanos: AtomicU64,
+}
+
+impl SilenceSkipper {
+    pub fn new(enabled: bool) -> Arc<Self> {
+        Arc::new(Self {
+            enabled: AtomicBool::new(enabled),
+            skipped_nanos: AtomicU64::new(0),
+        })
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Time dropped since the episode started or was last seeked.
+    pub fn skipped(&self) -> Duration {
+        Duration::from_nanos(self.skipped_nanos.load(Ordering::Relaxed))
+    }
+}
+
+/// Shortens every run of frames quieter than `threshold` to at most
+/// `min_silence`, so pauses stay audible but long gaps are cut.
+pub struct SkipSilence<S> {
+    input: S,
+    skipper: Arc<SilenceSkipper>,
+    threshold: f32,
+    min_silence: Duration,
+    silent_frames: u64,
+    frame: Vec<Sample>,
+    frame_pos: usize,
+}
+
+impl<S: Source> SkipSilence<S> {
+    pub fn new(
+        input: S,
+        skipper: Arc<SilenceSkipper>,
+        threshold_db: f32,
+        min_silence: Duration,
+    ) -> Self {
+        Self {
+            input,
+            skipper,
+            threshold: 10f32.powf(threshold_db / 20.0),
+            min_silence,
+            silent_frames: 0,
+            frame: Vec::new(),
+            frame_pos: 0,
+        }
+    }
+
+    /// Reads one sample per channel so frames are only ever dropped whole.
+    fn read_frame(&mut self) -> bool {
+        let channels = self.input.channels().max(1) as usize;
+        self.frame.clear();
+        self.frame_pos = 0;
+        self.frame.extend(self.input.by_ref().take(channels));
+        !self.frame.is_empty()
+    }
+}
+
+impl<S: Source> Iterator for SkipSilence<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        loop {
+            if let Some(&sample) = self.frame.get(self.frame_pos) {
+                self.frame_pos += 1;
+                return Some(sample);
+            }
+            if !self.read_frame() {
+                return None;
+            }
+
+            if !self
+                .frame
+                .iter()
+                .all(|sample| sample.abs() < self.threshold)
+            {
+                self.silent_frames = 0;
+                continue;
+            }
+            self.silent_frames += 1;
+            let sample_rate = u64::from(self.input.sample_rate().max(1));
+            let max_silent_frames =
+                self.min_silence.as_nanos() as u64 * sample_rate / 1_000_000_000;
+            if self.skipper.enabled.load(Ordering::Relaxed)
+                && self.silent_frames > max_silent_frames
+            {
+                self.skipper
+                    .skipped_nanos
+                    .fetch_add(1_000_000_000 / sample_rate, Ordering::Relaxed);
+                self.frame.clear();
+            }
+        }
+    }
+}
+
+impl<S: Source> Source for SkipSilence<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        self.input
+            .current_span_len()
+            .map(|len| len + self.frame.len() - self.frame_pos)
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)?;
+        self.frame.clear();
+        self.frame_pos = 0;
+        self.silent_frames = 0;
+        self.skipper.skipped_nanos.store(0, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+/// Lets the player fade an episode out under the next one. The fade can only
+/// be started once playback nears the end, so it is triggered from outside.
+#[derive(Default)]
+pub struct Fader {
+    /// The length of the fade, or zero until it starts.
+    fade_nanos: AtomicU64,
+}
+
+impl Fader {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn start(&self, duration: Duration) {
+        self.fade_nanos
+            .store((duration.as_nanos() as u64).max(1), Ordering::Relaxed);
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.fade_nanos.load(Ordering::Relaxed) > 0
+    }
+}
+
+/// Ramps down to silence once its fader starts, then ends so the sink empties.
+pub struct FadeOut<S> {
+    input: S,
+    fader: Arc<Fader>,
+    fade_samples: u64,
+    remaining_samples: Option<u64>,
+}
+
+impl<S: Source> FadeOut<S> {
+    pub fn new(input: S, fader: Arc<Fader>) -> Self {
+        Self {
+            input,
+            fader,
+            fade_samples: 0,
+            remaining_samples: None,
+        }
+    }
+}
+
+impl<S: Source> Iterator for FadeOut<S> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        let remaining_samples = match self.remaining_samples {
+            Some(remaining_samples) => remaining_samples,
+            None => {
+                let fade_nanos = self.fader.fade_nanos.load(Ordering::Relaxed);
+                if fade_nanos == 0 {
+                    return self.input.next();
+                }
+                let samples_per_sec =
+                    u64::from(self.input.sample_rate()) * u64::from(self.input.channels());
+                self.fade_samples = (fade_nanos * samples_per_sec / 1_000_000_000).max(1);
+                self.fade_samples
+            }
+        };
+        if remaining_samples == 0 {
+            return None;
+        }
+        self.remaining_samples = Some(remaining_samples - 1);
+        let sample = self.input.next()?;
+        Some(sample * remaining_samples as f32 / self.fade_samples as f32)
+    }
+}
+
+impl<S: Source> Source for FadeOut<S> {
+    fn current_span_len(&self) -> Option<usize> {
+        let len = self.input.current_span_len()?;
+        Some(match self.remaining_samples {
+            Some(remaining_samples) => len.min(remaining_samples as usize),
+            None => len,
+        })
+    }
+
+    fn channels(&self) -> ChannelCount {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> SampleRate {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+
+    fn try_seek(&mut self, pos: Duration) -> Result<(), SeekError> {
+        self.input.try_seek(pos)
+    }
+}
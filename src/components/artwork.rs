@@ -0,0 +1,43 @@
+use image::{RgbImage, imageops::FilterType};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    widgets::Widget,
+};
+
+pub struct Artwork<'a> {
+    image: &'a RgbImage,
+}
+
+impl<'a> Artwork<'a> {
+    pub fn new(image: &'a RgbImage) -> Self {
+        Self { image }
+    }
+}
+
+impl Widget for Artwork<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        // Each cell draws two vertical pixels with an upper half block, so the
+        // image is scaled to twice the row count to keep it roughly square.
+        let size = u32::from(area.width.min(area.height * 2));
+        let image = image::imageops::resize(self.image, size, size, FilterType::Triangle);
+        for y in 0..size / 2 {
+            for x in 0..size {
+                let top = image.get_pixel(x, y * 2);
+                let bottom = image.get_pixel(x, y * 2 + 1);
+                buf[(area.x + x as u16, area.y + y as u16)]
+                    .set_symbol("▀")
+                    .set_style(
+                        Style::new()
+                            .fg(Color::Rgb(top[0], top[1], top[2]))
+                            .bg(Color::Rgb(bottom[0], bottom[1], bottom[2])),
+                    );
+            }
+        }
+    }
+}
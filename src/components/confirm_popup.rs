@@ -0,0 +1,81 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Constraint, Flex, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::theme::Theme;
+
+#[derive(Default)]
+pub struct ConfirmPopupState {
+    title: String,
+    prompt: String,
+    decision: Option<bool>,
+}
+
+impl ConfirmPopupState {
+    pub fn new(title: &str, prompt: String) -> Self {
+        Self {
+            title: title.to_string(),
+            prompt,
+            decision: None,
+        }
+    }
+
+    pub fn handle_key(&mut self, key_code: KeyCode) {
+        match key_code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => self.decision = Some(true),
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => self.decision = Some(false),
+            _ => {}
+        }
+    }
+
+    pub fn decision(&self) -> Option<bool> {
+        self.decision
+    }
+}
+
+pub struct ConfirmPopup {
+    theme: Theme,
+}
+
+impl ConfirmPopup {
+    pub fn new(theme: Theme) -> Self {
+        Self { theme }
+    }
+}
+
+impl StatefulWidget for ConfirmPopup {
+    type State = ConfirmPopupState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let [popup_area] = Layout::vertical([Constraint::Length(6)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup_area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(popup_area);
+
+        let title_style = self.theme.title;
+        Clear.render(popup_area, buf);
+        Paragraph::new(vec![
+            Line::from(state.prompt.as_str()),
+            Line::default(),
+            Line::from(vec![
+                Span::styled("y", title_style),
+                Span::raw(": yes  "),
+                Span::styled("n", title_style),
+                Span::raw(": no"),
+            ]),
+        ])
+        .block(
+            Block::bordered()
+                .border_style(self.theme.border)
+                .title(Span::styled(state.title.as_str(), title_style)),
+        )
+        .wrap(Wrap { trim: true })
+        .render(popup_area, buf);
+    }
+}
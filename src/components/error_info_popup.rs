@@ -0,0 +1,75 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::{Constraint, Flex, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, StatefulWidget, Widget, Wrap},
+};
+
+use crate::theme::Theme;
+
+#[derive(Default)]
+pub struct ErrorInfoPopupState {
+    message: String,
+    dismissed: bool,
+}
+
+impl ErrorInfoPopupState {
+    pub fn new(message: String) -> Self {
+        Self {
+            message,
+            dismissed: false,
+        }
+    }
+
+    pub fn handle_key(&mut self, key_code: KeyCode) {
+        if matches!(key_code, KeyCode::Esc | KeyCode::Enter) {
+            self.dismissed = true;
+        }
+    }
+
+    pub fn is_dismissed(&self) -> bool {
+        self.dismissed
+    }
+}
+
+pub struct ErrorInfoPopup {
+    theme: Theme,
+}
+
+impl ErrorInfoPopup {
+    pub fn new(theme: Theme) -> Self {
+        Self { theme }
+    }
+}
+
+impl StatefulWidget for ErrorInfoPopup {
+    type State = ErrorInfoPopupState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let [popup_area] = Layout::vertical([Constraint::Length(7)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [popup_area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(popup_area);
+
+        let title_style = self.theme.title;
+        Clear.render(popup_area, buf);
+        Paragraph::new(vec![
+            Line::from(state.message.as_str()),
+            Line::default(),
+            Line::from(vec![
+                Span::styled("Esc", title_style),
+                Span::raw(": dismiss"),
+            ]),
+        ])
+        .block(
+            Block::bordered()
+                .border_style(self.theme.error_border)
+                .title(Span::styled("Error", title_style)),
+        )
+        .wrap(Wrap { trim: true })
+        .render(popup_area, buf);
+    }
+}
@@ -0,0 +1,4 @@
+pub mod artwork;
+pub mod confirm_popup;
+pub mod error_info_popup;
+pub mod text_input;
@@ -0,0 +1,132 @@
+use ratatui::{
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    layout::Rect,
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Paragraph, StatefulWidget, Widget},
+};
+
+#[derive(Default)]
+pub struct TextInputState {
+    value: String,
+    cursor: usize,
+}
+
+impl TextInputState {
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn set_value(&mut self, value: String) {
+        self.cursor = value.chars().count();
+        self.value = value;
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+        self.cursor = 0;
+    }
+
+    fn byte_index(&self, cursor: usize) -> usize {
+        self.value
+            .char_indices()
+            .nth(cursor)
+            .map_or(self.value.len(), |(index, _)| index)
+    }
+
+    pub fn handle_key(&mut self, key_code: KeyCode) -> bool {
+        let len = self.value.chars().count();
+        match key_code {
+            KeyCode::Char(c) => {
+                self.value.insert(self.byte_index(self.cursor), c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.value.remove(self.byte_index(self.cursor));
+            }
+            KeyCode::Delete if self.cursor < len => {
+                self.value.remove(self.byte_index(self.cursor));
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(len),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = len,
+            KeyCode::Backspace | KeyCode::Delete => {}
+            _ => return false,
+        }
+        true
+    }
+}
+
+pub struct TextInput<'a> {
+    label: &'a str,
+    label_style: Style,
+    block: Option<Block<'a>>,
+    show_cursor: bool,
+    masked: bool,
+}
+
+impl<'a> TextInput<'a> {
+    pub fn new(label: &'a str, label_style: Style) -> Self {
+        Self {
+            label,
+            label_style,
+            block: None,
+            show_cursor: true,
+            masked: false,
+        }
+    }
+
+    pub fn block(mut self, block: Block<'a>) -> Self {
+        self.block = Some(block);
+        self
+    }
+
+    pub fn show_cursor(mut self, show_cursor: bool) -> Self {
+        self.show_cursor = show_cursor;
+        self
+    }
+
+    pub fn masked(mut self, masked: bool) -> Self {
+        self.masked = masked;
+        self
+    }
+}
+
+impl StatefulWidget for TextInput<'_> {
+    type State = TextInputState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
+        let value = if self.masked {
+            "*".repeat(state.value.chars().count())
+        } else {
+            state.value.clone()
+        };
+        let (before, after): (String, String) = {
+            let mut chars = value.chars();
+            let before = chars.by_ref().take(state.cursor).collect();
+            (before, chars.collect())
+        };
+
+        let mut spans = vec![
+            Span::styled(self.label, self.label_style),
+            Span::raw(before),
+        ];
+        if self.show_cursor {
+            let mut after = after.chars();
+            let under_cursor = after.next().map_or(" ".to_string(), String::from);
+            spans.push(Span::raw(under_cursor).reversed());
+            spans.push(Span::raw(after.collect::<String>()));
+        } else {
+            spans.push(Span::raw(after));
+        }
+
+        let mut paragraph = Paragraph::new(Line::from(spans));
+        if let Some(block) = self.block {
+            paragraph = paragraph.block(block);
+        }
+        paragraph.render(area, buf);
+    }
+}
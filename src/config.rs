@@ -0,0 +1,106 @@
+use std::{fmt::Write, path::Path};
+
+use chrono::NaiveDate;
+
+use crate::AnyError;
+use crate::podcast::DEFAULT_FILENAME_TEMPLATE;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Config {
+    pub compact: bool,
+    pub volume: f32,
+    pub speed: f32,
+    pub auto_advance: bool,
+    pub stream_audio: bool,
+    pub theme: String,
+    pub http_timeout_secs: u64,
+    /// Overrides `HTTP_PROXY`/`HTTPS_PROXY`. An empty string connects directly.
+    pub proxy: Option<String>,
+    pub output_device: Option<String>,
+    /// Refreshes every feed in the background at this interval when set.
+    pub auto_update_minutes: Option<u64>,
+    pub max_concurrent_downloads: usize,
+    pub cache_limit_mb: Option<u64>,
+    pub wrap_navigation: bool,
+    /// A chrono format string for episode dates. Lists show relative dates when
+    /// this is unset.
+    pub date_format: Option<String>,
+    pub skip_silence: bool,
+    /// Frames quieter than this count as silence.
+    pub silence_threshold_db: f32,
+    /// Silences are shortened to this length rather than removed entirely.
+    pub min_silence_ms: u64,
+    /// Names downloads inside each podcast's directory. Supports `{podcast}`,
+    /// `{title}`, `{date}`, `{season}`, `{episode}` and `{hash}`, and `/` to
+    /// create subdirectories.
+    pub filename_template: String,
+    /// Overlaps queued and auto-advanced episodes by this long, fading one into
+    /// the next. Zero plays them back to back.
+    pub crossfade_secs: u64,
+    /// Asks the server for an episode's size when its info popup opens.
+    pub fetch_episode_size: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            compact: false,
+            volume: 1.0,
+            speed: 1.0,
+            auto_advance: false,
+            stream_audio: true,
+            theme: "default".to_string(),
+            http_timeout_secs: 15,
+            proxy: None,
+            output_device: None,
+            auto_update_minutes: None,
+            max_concurrent_downloads: 2,
+            cache_limit_mb: None,
+            wrap_navigation: false,
+            date_format: None,
+            skip_silence: false,
+            silence_threshold_db: -40.0,
+            min_silence_ms: 500,
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+            crossfade_secs: 0,
+            fetch_episode_size: true,
+        }
+    }
+}
+
+pub const ISO_DATE_FORMAT: &str = "%Y-%m-%d";
+
+impl Config {
+    /// Falls back to ISO dates when `date_format` can't format a date, such as
+    /// an unknown specifier or one that needs a time of day.
+    pub fn date_format(&self) -> Option<&str> {
+        let format = self.date_format.as_deref()?;
+        let sample = NaiveDate::from_ymd_opt(2024, 1, 15)?;
+        let mut formatted = String::new();
+        Some(if write!(formatted, "{}", sample.format(format)).is_ok() {
+            format
+        } else {
+            ISO_DATE_FORMAT
+        })
+    }
+}
+
+pub const CONFIG_FILE: &str = "config.json";
+
+pub async fn load_config_from_path(path: &Path) -> Result<Config, AnyError> {
+    let config_file = path.join(CONFIG_FILE);
+    if !config_file.exists() {
+        return Ok(Config::default());
+    }
+
+    let json = tokio::fs::read_to_string(config_file).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub async fn save_config_to_path(config: &Config, path: &Path) -> Result<(), AnyError> {
+    let json = serde_json::to_string(config)?;
+    tokio::fs::write(path.join(CONFIG_FILE), json).await?;
+    Ok(())
+}
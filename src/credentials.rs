@@ -0,0 +1,92 @@
+use std::{collections::HashMap, fmt, path::Path};
+
+use crate::AnyError;
+use serde::{Deserialize, Serialize};
+use tokio::io::AsyncWriteExt;
+
+/// Kept out of the podcast's `feed.json` so exported or shared feed data never
+/// carries secrets.
+pub const CREDENTIALS_FILE: &str = "credentials.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct FeedCredentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl fmt::Debug for FeedCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeedCredentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Credentials keyed by podcast feed url.
+pub type FeedCredentialStore = HashMap<String, FeedCredentials>;
+
+pub async fn load_credentials_from_path(path: &Path) -> Result<FeedCredentialStore, AnyError> {
+    let credentials_file = path.join(CREDENTIALS_FILE);
+    if !credentials_file.exists() {
+        return Ok(FeedCredentialStore::new());
+    }
+
+    let json = tokio::fs::read_to_string(credentials_file).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub async fn save_credentials_to_path(
+    credentials: &FeedCredentialStore,
+    path: &Path,
+) -> Result<(), AnyError> {
+    let credentials_file = path.join(CREDENTIALS_FILE);
+    let temp_file = path.join(format!("{CREDENTIALS_FILE}.tmp"));
+    let json = serde_json::to_string(credentials)?;
+
+    // The file is created private before any password is written to it, then
+    // renamed over the old one, so other users never get a window to read it.
+    // A leftover temp file may have looser permissions, so it's never reused.
+    _ = tokio::fs::remove_file(&temp_file).await;
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(&temp_file).await?;
+    file.write_all(json.as_bytes()).await?;
+    file.sync_all().await?;
+    drop(file);
+    tokio::fs::rename(&temp_file, &credentials_file).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn saved_credentials_are_only_readable_by_the_owner() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("teapod-credentials-{}", std::process::id()));
+        tokio::fs::create_dir_all(&path).await.unwrap();
+        let credentials = FeedCredentialStore::from([(
+            "https://example.com/feed".to_string(),
+            FeedCredentials {
+                username: "user".to_string(),
+                password: "secret".to_string(),
+            },
+        )]);
+        save_credentials_to_path(&credentials, &path).await.unwrap();
+        let mode = std::fs::metadata(path.join(CREDENTIALS_FILE))
+            .unwrap()
+            .permissions()
+            .mode();
+        let loaded = load_credentials_from_path(&path).await.unwrap();
+        std::fs::remove_dir_all(&path).unwrap();
+
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(loaded["https://example.com/feed"].password, "secret");
+    }
+}
@@ -0,0 +1,154 @@
+use std::{error::Error, fmt, time::Duration};
+
+use crate::AnyError;
+
+pub const USER_AGENT: &str = concat!("teapod/", env!("CARGO_PKG_VERSION"));
+const MAX_ATTEMPTS: u32 = 3;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// Feeds and enclosures commonly hop through a tracker and a CDN, but a longer
+/// chain is almost certainly a redirect loop.
+const MAX_REDIRECTS: usize = 10;
+
+#[derive(Debug)]
+pub struct RetryError {
+    attempts: u32,
+    source: reqwest::Error,
+}
+
+impl fmt::Display for RetryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (gave up after {} attempts)",
+            self.source, self.attempts
+        )
+    }
+}
+
+impl Error for RetryError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Builds the client shared by every feed, artwork, audio and search request so
+/// connections to the same host are reused.
+///
+/// `timeout` bounds connecting and each read rather than the whole request, so a
+/// stalled server fails quickly while long episode downloads still complete.
+///
+/// `proxy` replaces the proxy reqwest picks up from `HTTP_PROXY`/`HTTPS_PROXY`,
+/// and an empty string connects directly.
+pub fn build_http_client(
+    timeout: Duration,
+    proxy: Option<&str>,
+) -> Result<reqwest::Client, AnyError> {
+    let mut builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .redirect(reqwest::redirect::Policy::limited(MAX_REDIRECTS))
+        .connect_timeout(timeout)
+        .read_timeout(timeout);
+    match proxy {
+        Some("") => builder = builder.no_proxy(),
+        Some(proxy) => {
+            let proxy = reqwest::Proxy::all(proxy)
+                .map_err(|err| format!("invalid proxy {proxy}: {err}"))?;
+            builder = builder.proxy(proxy);
+        }
+        None => {}
+    }
+    Ok(builder.build()?)
+}
+
+const PROXY_ENV_VARS: [&str; 6] = [
+    "HTTPS_PROXY",
+    "https_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+];
+
+/// Names the proxy requests go through, for error messages. Credentials are
+/// left out so they never end up on screen.
+pub fn proxy_label(proxy: Option<&str>) -> Option<String> {
+    let proxy = match proxy {
+        Some(proxy) => proxy.to_string(),
+        None => PROXY_ENV_VARS
+            .iter()
+            .find_map(|name| std::env::var(name).ok())?,
+    };
+    if proxy.is_empty() {
+        return None;
+    }
+    Some(match reqwest::Url::parse(&proxy) {
+        Ok(url) => match (url.host_str(), url.port_or_known_default()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            _ => proxy,
+        },
+        // reqwest also accepts a bare host:port.
+        Err(_) => proxy.rsplit('@').next().unwrap_or_default().to_string(),
+    })
+}
+
+/// Sends `req`, retrying connection errors, timeouts and 5xx responses with
+/// exponential backoff. Client errors such as 404 are returned immediately.
+pub async fn send_with_retry(req: reqwest::RequestBuilder) -> Result<reqwest::Response, AnyError> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let Some(attempt_req) = req.try_clone() else {
+            return Ok(req.send().await?);
+        };
+        let err = match attempt_req.send().await {
+            Ok(res) if res.status().is_server_error() => res.error_for_status().unwrap_err(),
+            Ok(res) => return Ok(res),
+            Err(err) if err.is_connect() || err.is_timeout() || err.is_request() => err,
+            Err(err) => return Err(err.into()),
+        };
+        if attempt == MAX_ATTEMPTS {
+            return Err(RetryError {
+                attempts: attempt,
+                source: err,
+            }
+            .into());
+        }
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+}
+
+/// Replaces reqwest's timeout error with a message saying what timed out, and
+/// connection errors with one blaming the proxy when `proxy` is set, since
+/// every connection goes to it.
+pub fn describe_http_error(err: AnyError, what: &str, proxy: Option<&str>) -> AnyError {
+    let (reqwest_err, attempts) = if let Some(retry_err) = err.downcast_ref::<RetryError>() {
+        (&retry_err.source, Some(retry_err.attempts))
+    } else if let Some(reqwest_err) = err.downcast_ref::<reqwest::Error>() {
+        (reqwest_err, None)
+    } else {
+        return err;
+    };
+    let mut message = if reqwest_err.is_timeout() {
+        format!("{what} timed out")
+    } else if let Some(proxy) = proxy
+        && reqwest_err.is_connect()
+    {
+        let mut cause: &dyn Error = reqwest_err;
+        while let Some(source) = cause.source() {
+            cause = source;
+        }
+        format!("couldn't connect through proxy {proxy} ({cause}) for {what}")
+    } else {
+        return err;
+    };
+    if let Some(url) = reqwest_err.url() {
+        message += &format!(": {url}");
+    }
+    if let Some(attempts) = attempts {
+        message += &format!(" (gave up after {attempts} attempts)");
+    }
+    message.into()
+}
@@ -1,391 +1,4840 @@
-use std::{error::Error, fs::File, io::BufReader, time::Duration};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
+};
 
+use chrono::{Local, NaiveDate};
 use ratatui::{
-    crossterm::event::{self, Event, KeyCode, KeyEventKind},
-    layout::{Constraint, Direction, Layout},
+    Frame,
+    crossterm::{
+        event::{
+            self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+            KeyModifiers, MouseButton, MouseEventKind,
+        },
+        execute,
+    },
+    layout::{Constraint, Direction, Flex, Layout, Margin, Position, Rect},
     style::{Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, List, ListState, Paragraph, Row, Table, TableState, Wrap},
+    widgets::{
+        Block, Cell, LineGauge, List, ListState, Paragraph, Row, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Table, TableState, Wrap,
+    },
 };
-use rodio::{Sink, Source};
+use rodio::{Sink, Source, mixer::Mixer};
+use tokio::sync::mpsc::UnboundedSender;
 
+use crate::audio::{
+    FadeOut, Fader, SilenceSkipper, SkipSilence, open_output_stream, output_device_names,
+};
+use crate::components::{
+    artwork::Artwork,
+    confirm_popup::{ConfirmPopup, ConfirmPopupState},
+    error_info_popup::{ErrorInfoPopup, ErrorInfoPopupState},
+    text_input::{TextInput, TextInputState},
+};
+use crate::config::{Config, ISO_DATE_FORMAT, load_config_from_path, save_config_to_path};
+use crate::credentials::{
+    FeedCredentialStore, FeedCredentials, load_credentials_from_path, save_credentials_to_path,
+};
+use crate::http::{build_http_client, proxy_label};
+use crate::opml::{export_opml, import_opml, import_url_list};
 use crate::podcast::{
-    PODCAST_FEED_FILE, Podcast, check_podcast_audio_in_path, download_podcast_audio_to_path,
-    download_podcast_info_from_url, save_podcast_info_to_path, update_all_podcast_info,
+    Episode, EpisodeType, FeedValidators, Podcast, audio_cache_size_in_path,
+    cached_podcast_audio_in_path, check_podcast_audio_in_path, load_podcast_artwork_from_path,
+    load_podcast_episodes_from_path, load_podcast_order_from_path, load_podcast_summary_from_dir,
+    merge_episode_state, podcast_dir, remove_podcast_audio_in_path, save_podcast_info_to_path,
+    save_podcast_order_to_path,
 };
+use crate::search::SearchResult;
+use crate::session::{Session, load_session_from_path, save_session_to_path};
+use crate::stats::{ListeningStats, STATS_FILE, load_stats_from_path, save_stats_to_path};
+use crate::stream::StreamHandle;
+use crate::theme::Theme;
+use crate::transcript::{format_transcript, has_timestamps};
+use crate::worker::{EpisodeAudio, FeedRequestKind, WorkerEvent, WorkerRequest, spawn_worker};
 
+mod audio;
+mod components;
+mod config;
+mod credentials;
+mod http;
+mod opml;
 mod podcast;
+mod search;
+mod session;
+mod stats;
+mod stream;
+mod theme;
+mod transcript;
+mod worker;
 
 type AnyError = Box<dyn Send + Sync + Error>;
 
 enum ViewKind {
     PodcastInfo,
     AddPodcast,
+    SearchPodcast,
     EpisodeList,
     EpisodeInfo,
+    Inbox,
+    Queue,
+    OutputDevice,
+    SleepTimer,
+    Chapters,
+    GoToTime,
+    Transcript,
+    Stats,
+    Failures,
+    ExportEpisode(String),
+    Confirm(ConfirmAction),
+    ErrorInfo,
+    ImportSubscriptions,
+    ExportOpml,
+}
+
+impl ViewKind {
+    /// Views that index `podcasts` with `current_podcast`.
+    fn shows_current_podcast(&self) -> bool {
+        matches!(
+            self,
+            Self::PodcastInfo | Self::EpisodeList | Self::EpisodeInfo
+        )
+    }
+}
+
+/// Episodes are referred to by url rather than index, since a feed update can
+/// reorder them while the popup is open.
+#[derive(Clone)]
+enum ConfirmAction {
+    DeletePodcast(String),
+    DeleteDownload(String),
+    MarkAllUnplayed(String),
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+enum AddPodcastField {
+    #[default]
+    Url,
+    Username,
+    Password,
+}
+
+impl AddPodcastField {
+    fn next(self) -> Self {
+        match self {
+            Self::Url => Self::Username,
+            Self::Username => Self::Password,
+            Self::Password => Self::Url,
+        }
+    }
+
+    fn previous(self) -> Self {
+        match self {
+            Self::Url => Self::Password,
+            Self::Username => Self::Url,
+            Self::Password => Self::Username,
+        }
+    }
 }
 
+const INBOX_SIZE: usize = 50;
+const ARTWORK_WIDTH: u16 = 32;
+const MOUSE_SCROLL_LINES: u16 = 3;
+const VOLUME_STEP: f32 = 0.05;
+const MAX_VOLUME: f32 = 2.0;
+const GAIN_STEP_DB: f32 = 1.0;
+const MAX_GAIN_DB: f32 = 12.0;
+const SPEED_STEP: f32 = 0.1;
+const MIN_SPEED: f32 = 0.5;
+const MAX_SPEED: f32 = 3.0;
+/// A day, which keeps the deadline well within what `Instant` can represent.
+const MAX_SLEEP_MINUTES: u64 = 24 * 60;
+/// Starts loading the next episode this long before a crossfade, so a slow
+/// download still has time to finish.
+const PRELOAD_LEAD: Duration = Duration::from_secs(30);
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
 struct PlayerState {
     title: String,
+    podcast_url: String,
+    episode_url: String,
     sink: Sink,
-    duration: Duration,
+    duration: Option<Duration>,
+    stream: Option<StreamHandle>,
+    silence: Arc<SilenceSkipper>,
+    /// The podcast's gain as a linear factor on top of the volume.
+    gain: f32,
+    /// A-B repeat points. Playback jumps back to the start on reaching the end
+    /// once both are set.
+    loop_start: Option<Duration>,
+    loop_end: Option<Duration>,
+    fader: Arc<Fader>,
+}
+
+impl PlayerState {
+    fn set_volume(&self, volume: f32) {
+        self.sink.set_volume(volume * self.gain);
+    }
+
+    fn loop_label(&self) -> Option<String> {
+        match (self.loop_start, self.loop_end) {
+            (Some(start), Some(end)) => Some(format!(
+                "{}-{}",
+                format_audio_duration(start),
+                format_audio_duration(end)
+            )),
+            (Some(start), None) => Some(format!("{}-", format_audio_duration(start))),
+            _ => None,
+        }
+    }
+
+    /// The position in the episode, counting any silence that was skipped.
+    fn position(&self) -> Duration {
+        self.sink.get_pos() + self.silence.skipped()
+    }
+}
+
+/// The episode after the current one, loaded ahead of time so it can fade in
+/// over the end of the current one.
+enum Preload {
+    Loading(String),
+    Ready(String, EpisodeAudio),
+    Failed(String),
+}
+
+impl Preload {
+    fn episode_url(&self) -> &str {
+        match self {
+            Self::Loading(episode_url)
+            | Self::Ready(episode_url, _)
+            | Self::Failed(episode_url) => episode_url,
+        }
+    }
+}
+
+/// An episode fading out under the one that took over from it.
+struct FadingEpisode {
+    player_state: PlayerState,
+    next_episode_url: String,
+}
+
+struct TranscriptView {
+    title: String,
+    mime_type: String,
+    text: String,
+    with_timestamps: bool,
+}
+
+/// What a batch couldn't finish, listed once the rest of it is done.
+struct FailureReport {
+    title: String,
+    failures: Vec<String>,
+}
+
+struct QueuedEpisode {
+    title: String,
+    podcast_url: String,
+    episode_url: String,
+}
+
+impl QueuedEpisode {
+    fn new(podcast: &Podcast, episode: &Episode) -> Self {
+        Self {
+            title: format!("{} / {}", podcast.title, episode.title),
+            podcast_url: podcast.url.clone(),
+            episode_url: episode.url.clone(),
+        }
+    }
+}
+
+struct BatchProgress {
+    verb: &'static str,
+    total: usize,
+    completed: usize,
+    skipped: usize,
+    failed: Vec<String>,
+    new_episodes: usize,
+}
+
+impl BatchProgress {
+    fn new(verb: &'static str, total: usize, skipped: usize) -> Self {
+        Self {
+            verb,
+            total,
+            completed: 0,
+            skipped,
+            failed: Vec::new(),
+            new_episodes: 0,
+        }
+    }
+
+    fn label(&self) -> String {
+        format!("{} {}/{}…", self.verb, self.completed, self.total)
+    }
+
+    fn is_done(&self) -> bool {
+        self.completed >= self.total
+    }
+}
+
+/// Downloads queued by "download all new". Requests are handed to the worker
+/// a few at a time so a long backlog doesn't saturate the connection.
+struct DownloadBatch {
+    progress: BatchProgress,
+    pending: VecDeque<(Podcast, Episode)>,
+    in_flight: HashSet<String>,
+}
+
+impl DownloadBatch {
+    fn send_next(
+        &mut self,
+        worker: &UnboundedSender<WorkerRequest>,
+        max_concurrent: usize,
+    ) -> Result<(), AnyError> {
+        while self.in_flight.len() < max_concurrent.max(1)
+            && let Some((podcast, episode)) = self.pending.pop_front()
+        {
+            self.in_flight.insert(episode.url.clone());
+            worker
+                .send(WorkerRequest::DownloadEpisode {
+                    podcast: Box::new(podcast),
+                    episode: Box::new(episode),
+                })
+                .map_err(|_| "background worker stopped")?;
+        }
+        Ok(())
+    }
+
+    fn cancel(&mut self) -> usize {
+        let cancelled = self.pending.len();
+        self.pending.clear();
+        self.progress.total -= cancelled;
+        cancelled
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum EpisodeSort {
+    #[default]
+    Feed,
+    NewestFirst,
+    OldestFirst,
+    Numbered,
+}
+
+impl EpisodeSort {
+    fn next(self) -> Self {
+        match self {
+            Self::Feed => Self::NewestFirst,
+            Self::NewestFirst => Self::OldestFirst,
+            Self::OldestFirst => Self::Numbered,
+            Self::Numbered => Self::Feed,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+enum DownloadFilter {
+    #[default]
+    All,
+    Downloaded,
+    NotDownloaded,
+}
+
+impl DownloadFilter {
+    fn next(self) -> Self {
+        match self {
+            Self::All => Self::Downloaded,
+            Self::Downloaded => Self::NotDownloaded,
+            Self::NotDownloaded => Self::All,
+        }
+    }
+}
+
+#[derive(Default)]
+struct EpisodeFilter {
+    full_only: bool,
+    hide_explicit: bool,
+    downloads: DownloadFilter,
+    /// Urls of the episodes found in the audio cache, refreshed when the cache
+    /// changes so filtering doesn't check the disk on every frame.
+    downloaded: HashSet<String>,
+    query: String,
+    sort: EpisodeSort,
+}
+
+impl EpisodeFilter {
+    fn label(&self) -> String {
+        let mut label = String::new();
+        if self.full_only {
+            label.push_str(" [full only]");
+        }
+        if self.hide_explicit {
+            label.push_str(" [no explicit]");
+        }
+        match self.downloads {
+            DownloadFilter::All => {}
+            DownloadFilter::Downloaded => label.push_str(" [downloaded]"),
+            DownloadFilter::NotDownloaded => label.push_str(" [not downloaded]"),
+        }
+        match self.sort {
+            EpisodeSort::Feed => {}
+            EpisodeSort::NewestFirst => label.push_str(" [newest first]"),
+            EpisodeSort::OldestFirst => label.push_str(" [oldest first]"),
+            EpisodeSort::Numbered => label.push_str(" [by episode number]"),
+        }
+        label
+    }
+}
+
+fn downloaded_episode_urls(
+    podcasts: &[Podcast],
+    data_path: &Path,
+    filename_template: &str,
+) -> HashSet<String> {
+    podcasts
+        .iter()
+        .flat_map(|podcast| {
+            podcast.episodes.iter().filter(|episode| {
+                check_podcast_audio_in_path(podcast, episode, data_path, filename_template)
+            })
+        })
+        .map(|episode| episode.url.clone())
+        .collect()
+}
+
+fn matches_query(text: &str, query: &str) -> bool {
+    text.to_lowercase().contains(&query.to_lowercase())
+}
+
+fn filter_podcasts(podcasts: &[Podcast], query: &str) -> Vec<usize> {
+    podcasts
+        .iter()
+        .enumerate()
+        .filter(|(_, podcast)| matches_query(&podcast.title, query))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+fn filter_episodes(podcast: &Podcast, filter: &EpisodeFilter) -> Vec<usize> {
+    let mut episode_indices = podcast
+        .episodes
+        .iter()
+        .enumerate()
+        .filter(|(_, episode)| !filter.full_only || episode.episode_type == EpisodeType::Full)
+        .filter(|(_, episode)| !filter.hide_explicit || !episode.explicit)
+        .filter(|(_, episode)| match filter.downloads {
+            DownloadFilter::All => true,
+            DownloadFilter::Downloaded => filter.downloaded.contains(&episode.url),
+            DownloadFilter::NotDownloaded => !filter.downloaded.contains(&episode.url),
+        })
+        .filter(|(_, episode)| {
+            matches_query(&episode.title, &filter.query)
+                || matches_query(&episode.description, &filter.query)
+        })
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    match filter.sort {
+        EpisodeSort::Feed => {}
+        EpisodeSort::NewestFirst => episode_indices
+            .sort_by_key(|&index| std::cmp::Reverse(podcast.episodes[index].published_date())),
+        EpisodeSort::OldestFirst => {
+            episode_indices.sort_by_key(|&index| podcast.episodes[index].published_date())
+        }
+        // Unnumbered episodes sort after numbered ones instead of before them.
+        EpisodeSort::Numbered => episode_indices.sort_by_key(|&index| {
+            let episode = &podcast.episodes[index];
+            (
+                episode.season.is_none(),
+                episode.season,
+                episode.number.is_none(),
+                episode.number,
+            )
+        }),
+    }
+    episode_indices
+}
+
+/// The title cell shared by the episode list and the inbox, prefixed with the
+/// episode type and season/episode numbering.
+fn episode_title_line(episode: &Episode) -> Line<'_> {
+    Line::from(vec![
+        Span::raw(if episode.favorite { "★ " } else { "" }),
+        Span::styled(episode.episode_type.label(), Style::new().italic()),
+        Span::styled(
+            if episode.explicit { "[E] " } else { "" },
+            Style::new().bold(),
+        ),
+        Span::styled(
+            episode
+                .numbering()
+                .map(|numbering| format!("{numbering} "))
+                .unwrap_or_default(),
+            Style::new().dim(),
+        ),
+        Span::raw(episode.title.as_str()),
+    ])
+}
+
+/// Unplayed episodes across every podcast, or every favorite when
+/// `favorites_only` is set, newest first.
+fn inbox_episodes(podcasts: &[Podcast], favorites_only: bool) -> Vec<(usize, usize)> {
+    let mut entries = podcasts
+        .iter()
+        .enumerate()
+        .flat_map(|(podcast_index, podcast)| {
+            podcast
+                .episodes
+                .iter()
+                .enumerate()
+                .filter(|(_, episode)| {
+                    if favorites_only {
+                        episode.favorite
+                    } else {
+                        !episode.played
+                    }
+                })
+                .map(move |(episode_index, _)| (podcast_index, episode_index))
+        })
+        .collect::<Vec<_>>();
+    entries.sort_by(|&(a_podcast, a_episode), &(b_podcast, b_episode)| {
+        podcasts[b_podcast].episodes[b_episode]
+            .pub_date
+            .cmp(&podcasts[a_podcast].episodes[a_episode].pub_date)
+    });
+    if !favorites_only {
+        entries.truncate(INBOX_SIZE);
+    }
+    entries
+}
+
+fn next_episode_index(
+    podcast: &Podcast,
+    filter: &EpisodeFilter,
+    episode_url: &str,
+) -> Option<usize> {
+    let episode_indices = filter_episodes(podcast, filter);
+    let position = episode_indices
+        .iter()
+        .position(|&index| podcast.episodes[index].url == episode_url)?;
+    episode_indices.get(position + 1).copied()
+}
+
+fn selected_episode_index(table_state: &TableState, episode_indices: &[usize]) -> Option<usize> {
+    table_state
+        .selected()
+        .and_then(|selected| episode_indices.get(selected).copied())
+}
+
+fn select_episode_index(
+    table_state: &mut TableState,
+    episode_indices: &[usize],
+    episode_index: Option<usize>,
+) {
+    table_state.select(remap_selection(episode_indices, episode_index));
+}
+
+fn edit_search_query(query: &mut String, key_code: KeyCode) -> bool {
+    match key_code {
+        KeyCode::Esc => {
+            query.clear();
+            false
+        }
+        KeyCode::Enter => false,
+        KeyCode::Backspace => {
+            query.pop();
+            true
+        }
+        KeyCode::Char(c) => {
+            query.push(c);
+            true
+        }
+        _ => true,
+    }
+}
+
+/// Moves the selection down one row, wrapping to the top when `wrap` is set.
+fn next_selection(selected: Option<usize>, len: usize, wrap: bool) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    Some(match selected {
+        None => 0,
+        Some(selected) if selected >= last => {
+            if wrap {
+                0
+            } else {
+                last
+            }
+        }
+        Some(selected) => selected + 1,
+    })
+}
+
+/// Moves the selection up one row, wrapping to the bottom when `wrap` is set.
+fn previous_selection(selected: Option<usize>, len: usize, wrap: bool) -> Option<usize> {
+    let last = len.checked_sub(1)?;
+    Some(match selected {
+        None => last,
+        Some(0) => {
+            if wrap {
+                last
+            } else {
+                0
+            }
+        }
+        Some(selected) => selected.min(len) - 1,
+    })
+}
+
+/// Keeps a selection inside a list that may have shrunk.
+fn clamp_selection(selected: Option<usize>, len: usize) -> Option<usize> {
+    len.checked_sub(1)
+        .map(|last| selected.unwrap_or_default().min(last))
+}
+
+fn remap_selection(indices: &[usize], index: Option<usize>) -> Option<usize> {
+    index
+        .and_then(|index| indices.iter().position(|&i| i == index))
+        .or(if indices.is_empty() { None } else { Some(0) })
+}
+
+fn labeled_lines<'a>(label: &'a str, text: &'a str, label_style: Style) -> Vec<Line<'a>> {
+    let mut lines = text.lines().map(Line::raw).collect::<Vec<_>>();
+    match lines.first_mut() {
+        Some(first) => first.spans.insert(0, Span::styled(label, label_style)),
+        None => lines.push(Line::from(Span::styled(label, label_style))),
+    }
+    lines
+}
+
+#[derive(Default)]
+struct InfoScroll {
+    offset: u16,
+    page_height: u16,
+}
+
+impl InfoScroll {
+    fn handle_key(&mut self, key_event: KeyEvent) -> bool {
+        let page = self.page_height.max(1);
+        let is_ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+        match key_event.code {
+            KeyCode::Char('k') | KeyCode::Up => self.offset = self.offset.saturating_sub(1),
+            KeyCode::Char('j') | KeyCode::Down => self.offset = self.offset.saturating_add(1),
+            KeyCode::Char('u') if is_ctrl => self.offset = self.offset.saturating_sub(page),
+            KeyCode::Char('d') if is_ctrl => self.offset = self.offset.saturating_add(page),
+            KeyCode::PageUp => self.offset = self.offset.saturating_sub(page),
+            KeyCode::PageDown => self.offset = self.offset.saturating_add(page),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Maps a click to the index of the item under it in a bordered list drawn in
+/// `area`, where `header_rows` rows sit between the border and the first item.
+fn clicked_index(
+    area: Rect,
+    position: Position,
+    header_rows: u16,
+    offset: usize,
+    len: usize,
+) -> Option<usize> {
+    let inner = area.inner(Margin::new(1, 1));
+    let first_row = inner.y + header_rows;
+    if !inner.contains(position) || position.y < first_row {
+        return None;
+    }
+    let index = offset + usize::from(position.y - first_row);
+    (index < len).then_some(index)
+}
+
+/// How far a paragraph of `lines` wrapped to `width` columns can scroll before
+/// its last line reaches the bottom of a `height`-row area.
+fn max_scroll_offset(lines: &[Line], width: u16, height: u16) -> usize {
+    let line_count = lines
+        .iter()
+        .map(|line| line.width().max(1).div_ceil(width.max(1) as usize))
+        .sum::<usize>();
+    line_count.saturating_sub(height as usize)
+}
+
+fn render_info_paragraph(
+    frame: &mut Frame,
+    area: Rect,
+    lines: Vec<Line>,
+    block: Block,
+    scroll: &mut InfoScroll,
+) {
+    let inner = block.inner(area);
+    let max_offset = max_scroll_offset(&lines, inner.width, inner.height);
+    scroll.page_height = inner.height;
+    scroll.offset = scroll.offset.min(max_offset as u16);
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: true })
+            .scroll((scroll.offset, 0)),
+        area,
+    );
+    if max_offset > 0 {
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight),
+            area,
+            &mut ScrollbarState::new(max_offset + 1).position(scroll.offset as usize),
+        );
+    }
+}
+
+/// Draws `hint` in the middle of a list that has nothing to show.
+fn render_empty_hint(frame: &mut Frame, area: Rect, hint: &str) {
+    let [hint_area] = Layout::vertical([Constraint::Length(1)])
+        .flex(Flex::Center)
+        .areas(area);
+    frame.render_widget(Paragraph::new(hint).centered().dim(), hint_area);
+}
+
+/// A `12/340` style counter for list block titles.
+fn position_label(selected: Option<usize>, len: usize) -> Line<'static> {
+    let position = selected
+        .filter(|&selected| selected < len)
+        .map_or(0, |selected| selected + 1);
+    Line::from(format!("{position}/{len}")).right_aligned()
+}
+
+/// Moves a `height`-row window starting at `offset` just far enough to keep
+/// `selected` visible, without scrolling past the end of a `len`-row list.
+fn scroll_window(offset: usize, selected: usize, height: usize, len: usize) -> usize {
+    let offset = if selected < offset {
+        selected
+    } else if height > 0 && selected >= offset + height {
+        selected + 1 - height
+    } else {
+        offset
+    };
+    offset.min(len.saturating_sub(height))
+}
+
+fn format_audio_duration(duration: Duration) -> String {
+    let mut total_seconds = duration.as_secs();
+    let hours = total_seconds / (60 * 60);
+    total_seconds %= 60 * 60;
+    let minutes = total_seconds / 60;
+    let seconds = total_seconds % 60;
+    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+}
+
+fn format_relative_date(date: NaiveDate, today: NaiveDate) -> String {
+    let days = (today - date).num_days();
+    let plural =
+        |count: i64, unit: &str| format!("{count} {unit}{} ago", if count == 1 { "" } else { "s" });
+    match days {
+        ..0 => "upcoming".to_string(),
+        0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        2..=20 => plural(days, "day"),
+        21..=59 => plural(days / 7, "week"),
+        60..=364 => plural(days / 30, "month"),
+        _ => plural(days / 365, "year"),
+    }
+}
+
+fn format_episode_date(episode: &Episode, date_format: Option<&str>) -> String {
+    episode
+        .published_date()
+        .map(|date| match date_format {
+            Some(date_format) => date.format(date_format).to_string(),
+            None => format_relative_date(date, Local::now().date_naive()),
+        })
+        .unwrap_or_default()
+}
+
+fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Sums progress across every in-flight download; the percentage is only shown
+/// once all of them have reported a length.
+fn download_label(
+    pending_downloads: usize,
+    download_progress: &HashMap<String, (u64, Option<u64>)>,
+) -> Option<String> {
+    if pending_downloads == 0 && download_progress.is_empty() {
+        return None;
+    }
+
+    let mut label = if pending_downloads > 0 {
+        format!("Downloading {pending_downloads} episodes…")
+    } else {
+        "Downloading episode…".to_string()
+    };
+    if download_progress.is_empty() {
+        return Some(label);
+    }
+    let received: u64 = download_progress
+        .values()
+        .map(|(received, _)| received)
+        .sum();
+    let total_len: Option<u64> = download_progress.values().map(|(_, total)| *total).sum();
+    match total_len {
+        Some(total_len) if total_len > 0 => {
+            label += &format!(" {}%", received * 100 / total_len);
+        }
+        _ => label += &format!(" {}", format_file_size(received)),
+    }
+    Some(label)
+}
+
+/// Parses `MM:SS` or `HH:MM:SS`.
+fn parse_timestamp(text: &str) -> Option<Duration> {
+    let parts = text
+        .trim()
+        .split(':')
+        .map(|part| part.trim().parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (hours, minutes, seconds) = match parts[..] {
+        [minutes, seconds] => (0, minutes, seconds),
+        [hours, minutes, seconds] if minutes < 60 => (hours, minutes, seconds),
+        _ => return None,
+    };
+    if seconds >= 60 {
+        return None;
+    }
+    let secs = hours
+        .checked_mul(60)?
+        .checked_add(minutes)?
+        .checked_mul(60)?
+        .checked_add(seconds)?;
+    Some(Duration::from_secs(secs))
+}
+
+fn format_optional_duration(duration: Option<Duration>) -> String {
+    duration
+        .map(format_audio_duration)
+        .unwrap_or_else(|| "--:--:--".to_string())
+}
+
+/// Follow-up work for the main loop after a player key, for the cases that
+/// need more than the player itself.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum PlayerAction {
+    ConfigChanged,
+    Stop,
+    ShowChapters,
+    GoToTime,
+    SetLoopStart,
+    SetLoopEnd,
+    ClearLoop,
+}
+
+fn handle_player_key(
+    key_code: KeyCode,
+    player: &Option<PlayerState>,
+    config: &mut Config,
+) -> Option<PlayerAction> {
+    match key_code {
+        KeyCode::Char('c') => {
+            config.auto_advance = !config.auto_advance;
+            return Some(PlayerAction::ConfigChanged);
+        }
+        KeyCode::Char('z') => {
+            config.skip_silence = !config.skip_silence;
+            if let Some(player_state) = player {
+                player_state.silence.set_enabled(config.skip_silence);
+            }
+            return Some(PlayerAction::ConfigChanged);
+        }
+        // Also cancels an episode that is still loading.
+        KeyCode::Char('S') => return Some(PlayerAction::Stop),
+        _ => {}
+    }
+
+    let Some(player_state) = player else {
+        return None;
+    };
+
+    match key_code {
+        KeyCode::Char(' ') => {
+            if player_state.sink.is_paused() {
+                player_state.sink.play();
+            } else {
+                player_state.sink.pause();
+            }
+            None
+        }
+        KeyCode::Char('+') | KeyCode::Char('=') => {
+            config.volume = (config.volume + VOLUME_STEP).min(MAX_VOLUME);
+            player_state.set_volume(config.volume);
+            Some(PlayerAction::ConfigChanged)
+        }
+        KeyCode::Char('-') => {
+            config.volume = (config.volume - VOLUME_STEP).max(0.0);
+            player_state.set_volume(config.volume);
+            Some(PlayerAction::ConfigChanged)
+        }
+        KeyCode::Char('>') => {
+            config.speed = ((config.speed + SPEED_STEP) * 10.0).round() / 10.0;
+            config.speed = config.speed.min(MAX_SPEED);
+            player_state.sink.set_speed(config.speed);
+            Some(PlayerAction::ConfigChanged)
+        }
+        KeyCode::Char('<') => {
+            config.speed = ((config.speed - SPEED_STEP) * 10.0).round() / 10.0;
+            config.speed = config.speed.max(MIN_SPEED);
+            player_state.sink.set_speed(config.speed);
+            Some(PlayerAction::ConfigChanged)
+        }
+        // Emptying the sink hands over to the queue or auto-advance on the next tick.
+        KeyCode::Char('N') => {
+            player_state.sink.skip_one();
+            None
+        }
+        KeyCode::Char('C') => Some(PlayerAction::ShowChapters),
+        KeyCode::Char('p') => Some(PlayerAction::GoToTime),
+        KeyCode::Char('(') => Some(PlayerAction::SetLoopStart),
+        KeyCode::Char(')') => Some(PlayerAction::SetLoopEnd),
+        KeyCode::Char('L') => Some(PlayerAction::ClearLoop),
+        _ => None,
+    }
+}
+
+/// Adds the episode to the end of the queue unless it is already waiting there.
+fn enqueue_episode(
+    queue: &mut VecDeque<QueuedEpisode>,
+    podcast: &Podcast,
+    episode: &Episode,
+) -> String {
+    if queue.iter().any(|queued| queued.episode_url == episode.url) {
+        return format!("{} is already queued", episode.title);
+    }
+    queue.push_back(QueuedEpisode::new(podcast, episode));
+    format!("Queued {}", episode.title)
+}
+
+fn find_queued_episode(queued: &QueuedEpisode, podcasts: &[Podcast]) -> Option<(usize, usize)> {
+    podcasts
+        .iter()
+        .position(|podcast| podcast.url == queued.podcast_url)
+        .and_then(|podcast_index| {
+            podcasts[podcast_index]
+                .episodes
+                .iter()
+                .position(|episode| episode.url == queued.episode_url)
+                .map(|episode_index| (podcast_index, episode_index))
+        })
+}
+
+/// Pops queued entries until one still exists in the library, returning its
+/// podcast and episode indices.
+fn pop_queued_episode(
+    queue: &mut VecDeque<QueuedEpisode>,
+    podcasts: &[Podcast],
+) -> Option<(usize, usize)> {
+    while let Some(queued) = queue.pop_front() {
+        let found = find_queued_episode(&queued, podcasts);
+        if found.is_some() {
+            return found;
+        }
+    }
+    None
+}
+
+fn auto_advance_episode(
+    podcasts: &[Podcast],
+    filter: &EpisodeFilter,
+    player_state: &PlayerState,
+) -> Option<(usize, usize)> {
+    podcasts
+        .iter()
+        .position(|podcast| podcast.url == player_state.podcast_url)
+        .and_then(|podcast_index| {
+            next_episode_index(&podcasts[podcast_index], filter, &player_state.episode_url)
+                .map(|episode_index| (podcast_index, episode_index))
+        })
+}
+
+/// The episode that plays after the current one, without taking it off the
+/// queue.
+fn upcoming_episode(
+    queue: &VecDeque<QueuedEpisode>,
+    podcasts: &[Podcast],
+    config: &Config,
+    filter: &EpisodeFilter,
+    player_state: &PlayerState,
+) -> Option<(usize, usize)> {
+    queue
+        .iter()
+        .find_map(|queued| find_queued_episode(queued, podcasts))
+        .or_else(|| {
+            config
+                .auto_advance
+                .then(|| auto_advance_episode(podcasts, filter, player_state))
+                .flatten()
+        })
+}
+
+/// Opens the clipboard on first use so a headless session only fails the copy
+/// or paste that needed it instead of refusing to start.
+fn open_clipboard(
+    clipboard: &mut Option<arboard::Clipboard>,
+) -> Result<&mut arboard::Clipboard, AnyError> {
+    if clipboard.is_none() {
+        *clipboard = Some(arboard::Clipboard::new()?);
+    }
+    Ok(clipboard.as_mut().unwrap())
+}
+
+fn open_in_browser(url: &str) -> Result<(), AnyError> {
+    let mut command = if cfg!(target_os = "windows") {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    let mut child = command
+        .arg(url)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .map_err(|err| format!("failed to open {url}: {err}"))?;
+    // Reap the opener once it exits so it doesn't linger as a zombie.
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+fn find_episode<'a>(
+    podcasts: &'a [Podcast],
+    episode_url: &str,
+) -> Option<(&'a Podcast, &'a Episode)> {
+    podcasts.iter().find_map(|podcast| {
+        podcast
+            .episodes
+            .iter()
+            .find(|episode| episode.url == episode_url)
+            .map(|episode| (podcast, episode))
+    })
+}
+
+fn request_episode(
+    worker: &UnboundedSender<WorkerRequest>,
+    podcast: &Podcast,
+    episode: &Episode,
+    config: &Config,
+) -> Result<(String, String), AnyError> {
+    worker
+        .send(WorkerRequest::LoadEpisode {
+            podcast: Box::new(podcast.clone()),
+            episode: Box::new(episode.clone()),
+            stream_audio: config.stream_audio,
+        })
+        .map_err(|_| "background worker stopped")?;
+    Ok((
+        episode.url.clone(),
+        format!("{} / {}", &podcast.title, &episode.title),
+    ))
+}
+
+fn request_chapters(
+    worker: &UnboundedSender<WorkerRequest>,
+    episode: &Episode,
+) -> Result<(), AnyError> {
+    if episode.chapters.is_empty()
+        && let Some(chapters_url) = &episode.chapters_url
+    {
+        worker
+            .send(WorkerRequest::FetchChapters {
+                episode_url: episode.url.clone(),
+                chapters_url: chapters_url.clone(),
+            })
+            .map_err(|_| "background worker stopped")?;
+    }
+    Ok(())
+}
+
+fn episode_duration(audio: &EpisodeAudio, episode: &Episode) -> Option<Duration> {
+    audio
+        .total_duration
+        .or(episode.duration)
+        .filter(|duration| !duration.is_zero())
+}
+
+fn start_episode(
+    podcast: &Podcast,
+    episode: &Episode,
+    audio: EpisodeAudio,
+    mixer: &Mixer,
+    config: &Config,
+    fade_in: Option<Duration>,
+) -> PlayerState {
+    let title = format!("{} / {}", &podcast.title, &episode.title);
+    let sink = Sink::connect_new(mixer);
+    let gain = rodio::math::db_to_linear(podcast.gain_db);
+    sink.set_volume(config.volume * gain);
+    sink.set_speed(config.speed);
+    let duration = episode_duration(&audio, episode);
+    let silence = SilenceSkipper::new(config.skip_silence);
+    let fader = Fader::new();
+    let source = FadeOut::new(
+        SkipSilence::new(
+            audio.source,
+            silence.clone(),
+            config.silence_threshold_db,
+            Duration::from_millis(config.min_silence_ms),
+        ),
+        fader.clone(),
+    );
+    match fade_in {
+        Some(fade_in) => sink.append(source.fade_in(fade_in)),
+        None => sink.append(source),
+    }
+
+    if let Some(position_secs) = episode.position_secs {
+        let position = Duration::from_secs(position_secs);
+        if duration.is_none_or(|duration| position < duration) {
+            _ = sink.try_seek(position);
+        }
+    }
+
+    PlayerState {
+        title,
+        podcast_url: podcast.url.clone(),
+        episode_url: episode.url.clone(),
+        sink,
+        duration,
+        stream: audio.stream,
+        silence,
+        gain,
+        loop_start: None,
+        loop_end: None,
+        fader,
+    }
+}
+
+fn validate_feed_url(input: &str) -> Result<String, &'static str> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err("Enter a podcast url");
+    }
+    match reqwest::Url::parse(input) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") && url.has_host() => Ok(url.into()),
+        _ => Err("Not a valid http(s) url"),
+    }
+}
+
+fn normalize_feed_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_lowercase()
+}
+
+fn find_podcast_by_url<'a>(podcasts: &'a [Podcast], url: &str) -> Option<&'a Podcast> {
+    let url = normalize_feed_url(url);
+    podcasts
+        .iter()
+        .find(|podcast| normalize_feed_url(&podcast.url) == url)
+}
+
+async fn load_all_episodes(podcasts: &mut [Podcast], data_path: &Path) -> Result<(), AnyError> {
+    for podcast in podcasts {
+        load_podcast_episodes_from_path(podcast, data_path).await?;
+    }
+    Ok(())
+}
+
+fn request_feed_updates(
+    worker: &UnboundedSender<WorkerRequest>,
+    podcasts: &[Podcast],
+    feed_credentials: &FeedCredentialStore,
+    kind: FeedRequestKind,
+) -> Result<(), AnyError> {
+    for podcast in podcasts {
+        worker
+            .send(WorkerRequest::FetchFeed {
+                url: podcast.url.clone(),
+                kind,
+                validators: podcast.validators.clone(),
+                credentials: feed_credentials.get(&podcast.url).cloned(),
+            })
+            .map_err(|_| "background worker stopped")?;
+    }
+    Ok(())
+}
+
+/// Deletes the oldest downloads of played episodes until the cache fits in
+/// `limit` bytes. Favorites and the episode that's playing are never evicted.
+async fn evict_audio_cache(
+    podcasts: &mut [Podcast],
+    data_path: &Path,
+    filename_template: &str,
+    limit: u64,
+    playing_url: Option<&str>,
+) -> Result<usize, AnyError> {
+    let mut cache_size = audio_cache_size_in_path(data_path);
+    if cache_size <= limit {
+        return Ok(0);
+    }
+
+    load_all_episodes(podcasts, data_path).await?;
+    let mut candidates = podcasts
+        .iter()
+        .flat_map(|podcast| {
+            cached_podcast_audio_in_path(podcast, data_path, filename_template)
+                .into_iter()
+                .map(move |audio| (podcast, audio))
+        })
+        .filter(|(podcast, audio)| {
+            let episode = &podcast.episodes[audio.episode_index];
+            episode.played && !episode.favorite && playing_url != Some(episode.url.as_str())
+        })
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(_, audio)| audio.modified);
+
+    let mut evicted = 0;
+    for (podcast, audio) in candidates {
+        if cache_size <= limit {
+            break;
+        }
+        remove_podcast_audio_in_path(
+            podcast,
+            &podcast.episodes[audio.episode_index],
+            data_path,
+            filename_template,
+        )
+        .await?;
+        cache_size = cache_size.saturating_sub(audio.len);
+        evicted += 1;
+    }
+    Ok(evicted)
+}
+
+async fn add_podcast(
+    podcast: Podcast,
+    podcasts: &mut Vec<Podcast>,
+    data_path: &Path,
+) -> Result<(), AnyError> {
+    save_podcast_info_to_path(&podcast, data_path).await?;
+
+    podcasts.push(podcast);
+    save_podcast_order_to_path(podcasts, data_path).await
+}
+
+/// Only paths starting with `~/` need a home directory.
+fn expand_home_path(input: &str, home_path: Option<&Path>) -> Result<PathBuf, AnyError> {
+    Ok(match input.trim().strip_prefix("~/") {
+        Some(rest) => home_path
+            .ok_or("can't expand ~ without a home directory")?
+            .join(rest),
+        None => PathBuf::from(input.trim()),
+    })
+}
+
+fn resolve_data_path(
+    mut args: impl Iterator<Item = String>,
+    home_path: Option<&Path>,
+) -> Result<PathBuf, AnyError> {
+    let mut data_dir = None;
+    while let Some(arg) = args.next() {
+        if let Some(value) = arg.strip_prefix("--data-dir=") {
+            data_dir = Some(value.to_string());
+        } else if arg == "--data-dir" {
+            data_dir = Some(args.next().ok_or("--data-dir requires a path")?);
+        } else {
+            return Err(
+                format!("unknown argument {arg} (usage: teapod [--data-dir <path>])").into(),
+            );
+        }
+    }
+
+    let env_var = |name| std::env::var(name).ok().filter(|value| !value.is_empty());
+    if let Some(data_dir) = data_dir.or_else(|| env_var("TEAPOD_DATA_DIR")) {
+        return expand_home_path(&data_dir, home_path);
+    }
+    let data_home = env_var("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(dirs::data_dir)
+        .ok_or("no data directory found; pass --data-dir or set TEAPOD_DATA_DIR")?;
+    Ok(data_home.join("teapod"))
+}
+
+/// Set once the process is asked to stop from outside, such as by the terminal
+/// closing, so the main loop still saves its state on the way out.
+fn spawn_shutdown_listener() -> Arc<AtomicBool> {
+    let shutdown_requested = Arc::new(AtomicBool::new(false));
+    let flag = shutdown_requested.clone();
+    tokio::spawn(async move {
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{SignalKind, signal};
+            let (Ok(mut terminate), Ok(mut hangup)) = (
+                signal(SignalKind::terminate()),
+                signal(SignalKind::hangup()),
+            ) else {
+                return;
+            };
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+                _ = hangup.recv() => {}
+            }
+        }
+        #[cfg(not(unix))]
+        if tokio::signal::ctrl_c().await.is_err() {
+            return;
+        }
+        flag.store(true, Ordering::Relaxed);
+    });
+    shutdown_requested
+}
+
+async fn save_playback_position(
+    player_state: &PlayerState,
+    podcasts: &mut [Podcast],
+    stats: &mut ListeningStats,
+    data_path: &Path,
+) -> Result<(), AnyError> {
+    let Some(podcast) = podcasts
+        .iter_mut()
+        .find(|podcast| podcast.url == player_state.podcast_url)
+    else {
+        return Ok(());
+    };
+    let Some(episode) = podcast
+        .episodes
+        .iter_mut()
+        .find(|episode| episode.url == player_state.episode_url)
+    else {
+        return Ok(());
+    };
+
+    let position = player_state.position();
+    // A fading episode has handed over to the next one, so it counts as finished.
+    if player_state.sink.empty()
+        || player_state.fader.is_started()
+        || player_state
+            .duration
+            .is_some_and(|duration| position >= duration)
+    {
+        episode.position_secs = None;
+        if !episode.played {
+            episode.played = true;
+            let listened = player_state
+                .duration
+                .or(episode.duration)
+                .unwrap_or(position);
+            stats.record_played(podcast, listened);
+            save_stats_to_path(stats, data_path).await?;
+        }
+    } else {
+        episode.position_secs = Some(position.as_secs());
+    }
+    save_podcast_info_to_path(podcast, data_path).await
+}
+
+/// A saved position at or past the end of the audio means the episode was
+/// heard to the end, so it counts as played and starts over from the
+/// beginning.
+async fn reset_finished_position(
+    podcasts: &mut [Podcast],
+    episode_url: &str,
+    audio: &EpisodeAudio,
+    stats: &mut ListeningStats,
+    data_path: &Path,
+) -> Result<(), AnyError> {
+    for podcast in podcasts.iter_mut() {
+        let Some(episode_index) = podcast
+            .episodes
+            .iter()
+            .position(|episode| episode.url == episode_url)
+        else {
+            continue;
+        };
+
+        let episode = &mut podcast.episodes[episode_index];
+        let is_finished = episode.position_secs.is_some_and(|position_secs| {
+            episode_duration(audio, episode)
+                .is_some_and(|duration| Duration::from_secs(position_secs) >= duration)
+        });
+        if is_finished {
+            episode.position_secs = None;
+            mark_played(podcast, episode_index, stats);
+            save_podcast_info_to_path(podcast, data_path).await?;
+            save_stats_to_path(stats, data_path).await?;
+        }
+        break;
+    }
+    Ok(())
+}
+
+/// Marking an episode played counts its whole length as listened, since it
+/// was most likely heard elsewhere.
+fn mark_played(podcast: &mut Podcast, episode_index: usize, stats: &mut ListeningStats) {
+    let episode = &mut podcast.episodes[episode_index];
+    if !episode.played {
+        episode.played = true;
+        let listened = episode.duration.unwrap_or_default();
+        stats.record_played(podcast, listened);
+    }
+}
+
+/// Returns how many episodes changed.
+fn mark_all_episodes(podcast: &mut Podcast, played: bool, stats: &mut ListeningStats) -> usize {
+    let mut changed = 0;
+    for episode_index in 0..podcast.episodes.len() {
+        if podcast.episodes[episode_index].played == played {
+            continue;
+        }
+        if played {
+            mark_played(podcast, episode_index, stats);
+        } else {
+            podcast.episodes[episode_index].played = false;
+        }
+        changed += 1;
+    }
+    changed
+}
+
+fn format_gain(gain_db: f32) -> String {
+    format!("{gain_db:+.0} dB")
 }
 
-fn format_audio_duration(duration: Duration) -> String {
-    let mut total_seconds = duration.as_secs();
-    let hours = total_seconds / (60 * 60);
-    total_seconds %= 60 * 60;
-    let minutes = total_seconds / 60;
-    let seconds = total_seconds % 60;
-    format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+fn format_listening_time(secs: u64) -> String {
+    let hours = secs / (60 * 60);
+    let minutes = secs % (60 * 60) / 60;
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), AnyError> {
-    let home_path = std::env::home_dir().ok_or("missing home directory")?;
-    let data_path = home_path.join(".local/share/teapod");
-    if !data_path.exists() {
-        tokio::fs::create_dir_all(&data_path).await?;
-    }
+    let home_path = dirs::home_dir();
+    let data_path = resolve_data_path(std::env::args().skip(1), home_path.as_deref())?;
+    tokio::fs::create_dir_all(&data_path).await?;
 
     let mut podcasts = Vec::<Podcast>::new();
     let mut read_dir = tokio::fs::read_dir(&data_path).await?;
     while let Some(entry) = read_dir.next_entry().await? {
-        let feed_file = entry.path().join(PODCAST_FEED_FILE);
-        if feed_file.exists() {
-            let json = tokio::fs::read_to_string(&feed_file).await?;
-            let podcast = serde_json::from_str(&json)?;
+        if let Some(podcast) = load_podcast_summary_from_dir(&entry.path()).await? {
             podcasts.push(podcast);
         }
     }
 
-    let mut clipboard = arboard::Clipboard::new()?;
-    let stream_handle = {
-        let mut handle = rodio::OutputStreamBuilder::open_default_stream()?;
-        handle.log_on_drop(false);
-        handle
-    };
+    let podcast_order = load_podcast_order_from_path(&data_path).await?;
+    podcasts.sort_by_key(|podcast| {
+        podcast_order
+            .iter()
+            .position(|url| *url == podcast.url)
+            .unwrap_or(podcast_order.len())
+    });
+
+    let mut config = load_config_from_path(&data_path).await?;
+    let mut feed_credentials = load_credentials_from_path(&data_path).await?;
+
+    let mut clipboard: Option<arboard::Clipboard> = None;
+    let (mut stream_handle, is_device_found) = open_output_stream(config.output_device.as_deref())?;
     let mut player: Option<PlayerState> = None;
+    let mut loading_episode: Option<(String, String)> = None;
+    let mut preload: Option<Preload> = None;
+    let mut fading_episode: Option<FadingEpisode> = None;
+    let (worker, mut worker_events) = spawn_worker(
+        data_path.clone(),
+        config.filename_template.clone(),
+        proxy_label(config.proxy.as_deref()),
+        build_http_client(
+            Duration::from_secs(config.http_timeout_secs),
+            config.proxy.as_deref(),
+        )?,
+    );
 
     let mut terminal = ratatui::init();
+    execute!(std::io::stdout(), EnableMouseCapture)?;
+    // `ratatui::init` already restores the terminal on panic, but doesn't know
+    // about mouse capture.
+    let restore_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        _ = execute!(std::io::stdout(), DisableMouseCapture);
+        restore_hook(info);
+    }));
+    let shutdown_requested = spawn_shutdown_listener();
+    // Filled in while drawing so mouse events can be mapped back to widgets.
+    let mut list_area = Rect::default();
+    let mut player_area = Rect::default();
 
-    let title_style = Style::new().bold();
-    let table_header_style = Style::new().underlined();
+    let mut theme = Theme::from_name(&config.theme);
+    let date_format = config.date_format().map(str::to_string);
+    let date_format = date_format.as_deref();
+    // Wide enough for relative dates such as "11 months ago", or a long month
+    // name in the configured format.
+    let date_width = date_format.map_or(14, |date_format| {
+        NaiveDate::from_ymd_opt(2024, 9, 30).map_or(14, |date| {
+            date.format(date_format).to_string().chars().count().max(4) as u16
+        })
+    });
 
     let mut podcast_list_state = ListState::default();
+    let mut podcast_query = String::new();
+    let mut current_podcast = 0;
+    let mut is_searching = false;
     let mut episode_list_table_state = TableState::default();
+    let mut episode_filter = EpisodeFilter::default();
+    let mut episode_list_offset = 0;
+    let mut inbox_table_state = TableState::default();
+    let mut is_inbox_favorites = false;
+    let mut queue = VecDeque::<QueuedEpisode>::new();
+    let mut queue_list_state = ListState::default();
+    let mut chapter_list_state = ListState::default();
+    let mut pending_transcript: Option<String> = None;
+    // Sizes reported by the server, keyed by episode url. `None` means the
+    // server didn't send a length.
+    let mut episode_sizes: HashMap<String, Option<u64>> = HashMap::new();
+    let mut pending_episode_sizes: HashSet<String> = HashSet::new();
+    let mut transcript_view: Option<TranscriptView> = None;
+    let mut failure_report: Option<FailureReport> = None;
+    let mut info_scroll = InfoScroll::default();
+    let mut pending_g = false;
+    let mut podcast_artwork = None;
+    let mut podcast_audio_size = 0;
 
     let mut view_stack = Vec::<ViewKind>::new();
-    let mut add_podcast_url = String::new();
+    let mut add_podcast_input = TextInputState::default();
+    let mut add_username_input = TextInputState::default();
+    let mut add_password_input = TextInputState::default();
+    let mut add_podcast_field = AddPodcastField::default();
+    let mut search_input = TextInputState::default();
+    let mut is_editing_search = true;
+    let mut pending_search: Option<String> = None;
+    let mut search_results = Vec::<SearchResult>::new();
+    let mut search_results_state = ListState::default();
+    let mut opml_path_input = TextInputState::default();
+    let mut go_to_time_input = TextInputState::default();
+    let mut export_path_input = TextInputState::default();
+    let mut status_message: Option<String> = (!is_device_found).then(|| {
+        format!(
+            "Output device {} not found, using the default",
+            config.output_device.as_deref().unwrap_or_default()
+        )
+    });
+    let mut output_devices = Vec::<String>::new();
+    let mut output_device_state = ListState::default();
+    let mut update_progress: Option<BatchProgress> = None;
+    let mut auto_update_progress: Option<BatchProgress> = None;
+    let auto_update_interval = config
+        .auto_update_minutes
+        .filter(|&minutes| minutes > 0)
+        .map(|minutes| Duration::from_secs(minutes * 60));
+    let mut next_auto_update = auto_update_interval.map(|interval| Instant::now() + interval);
+    let mut import_progress: Option<BatchProgress> = None;
+    let mut pending_adds = 0;
+    let mut pending_downloads = 0;
+    let mut pending_exports = 0;
+    let mut download_batch: Option<DownloadBatch> = None;
+    let mut download_progress = HashMap::<String, (u64, Option<u64>)>::new();
+    let mut cache_size = 0;
+    let mut is_cache_changed = true;
+    let mut frame_count = 0;
+    let mut sleep_timer_minutes = String::new();
+    let mut sleep_deadline: Option<Instant> = None;
+    let mut confirm_popup_state = ConfirmPopupState::default();
+    let mut error_info_popup_state = ErrorInfoPopupState::default();
+
+    let mut stats = load_stats_from_path(&data_path).await?;
+    let session = load_session_from_path(&data_path).await?;
+    if let Some(podcast_index) = session
+        .podcast_url
+        .as_deref()
+        .and_then(|url| podcasts.iter().position(|podcast| podcast.url == url))
+    {
+        podcast_list_state.select(Some(podcast_index));
+        if session.episode_key.is_some() {
+            load_podcast_episodes_from_path(&mut podcasts[podcast_index], &data_path).await?;
+        }
+        let podcast = &podcasts[podcast_index];
+        if let Some(episode_index) = session.episode_key.as_deref().and_then(|key| {
+            podcast
+                .episodes
+                .iter()
+                .position(|episode| episode.key() == key)
+        }) {
+            current_podcast = podcast_index;
+            select_episode_index(
+                &mut episode_list_table_state,
+                &filter_episodes(podcast, &episode_filter),
+                Some(episode_index),
+            );
+            view_stack.push(ViewKind::EpisodeList);
+        }
+    }
 
     let mut should_quit = false;
-    while !should_quit {
-        terminal.draw(|frame| {
+    // Terminal errors end the loop rather than returning, so state is still
+    // saved and the terminal restored.
+    let mut loop_error: Option<std::io::Error> = None;
+    while !should_quit && !shutdown_requested.load(Ordering::Relaxed) {
+        frame_count += 1;
+        let tick_result: Result<(), AnyError> = async {
+            while let Ok(worker_event) = worker_events.try_recv() {
+                match worker_event {
+                    WorkerEvent::FeedFetched { url, kind, result } => match kind {
+                        FeedRequestKind::Add => {
+                            pending_adds -= 1;
+                            let credentials = feed_credentials.remove(&url);
+                            let Some(podcast) = result? else {
+                                continue;
+                            };
+                            if find_podcast_by_url(&podcasts, &podcast.url).is_some() {
+                                status_message =
+                                    Some(format!("Already subscribed to {}", podcast.title));
+                                continue;
+                            }
+                            if let Some(credentials) = credentials {
+                                feed_credentials.insert(podcast.url.clone(), credentials);
+                                save_credentials_to_path(&feed_credentials, &data_path).await?;
+                            }
+                            status_message = Some(if podcast.url == url {
+                                format!("Added {}", podcast.title)
+                            } else {
+                                format!("Added {} (feed moved to {})", podcast.title, podcast.url)
+                            });
+                            add_podcast(podcast, &mut podcasts, &data_path).await?;
+                        }
+                        FeedRequestKind::Import => {
+                            let Some(progress) = &mut import_progress else {
+                                continue;
+                            };
+                            progress.completed += 1;
+                            match result {
+                                Ok(Some(podcast)) => {
+                                    if find_podcast_by_url(&podcasts, &podcast.url).is_none() {
+                                        add_podcast(podcast, &mut podcasts, &data_path).await?;
+                                    }
+                                }
+                                Ok(None) => {}
+                                Err(err) => progress.failed.push(format!("{url}: {err}")),
+                            }
+                        }
+                        FeedRequestKind::Update | FeedRequestKind::AutoUpdate => {
+                            let progress = if kind == FeedRequestKind::Update {
+                                &mut update_progress
+                            } else {
+                                &mut auto_update_progress
+                            };
+                            let Some(progress) = progress else {
+                                continue;
+                            };
+                            progress.completed += 1;
+                            match result {
+                                Ok(None) => {}
+                                Ok(Some(mut podcast)) => {
+                                    if let Some(index) = podcasts.iter().position(|p| p.url == url)
+                                    {
+                                        // New episodes shift indices, so keep the
+                                        // selections on the same episodes.
+                                        let selected_key = (index == current_podcast)
+                                            .then(|| {
+                                                selected_episode_index(
+                                                    &episode_list_table_state,
+                                                    &filter_episodes(
+                                                        &podcasts[index],
+                                                        &episode_filter,
+                                                    ),
+                                                )
+                                            })
+                                            .flatten()
+                                            .map(|episode_index| {
+                                                podcasts[index].episodes[episode_index]
+                                                    .key()
+                                                    .to_string()
+                                            });
+                                        let inbox_key = inbox_table_state
+                                            .selected()
+                                            .and_then(|selected| {
+                                                inbox_episodes(&podcasts, is_inbox_favorites)
+                                                    .get(selected)
+                                                    .copied()
+                                            })
+                                            .map(|(podcast_index, episode_index)| {
+                                                (
+                                                    podcast_index,
+                                                    podcasts[podcast_index].episodes[episode_index]
+                                                        .key()
+                                                        .to_string(),
+                                                )
+                                            });
+
+                                        load_podcast_episodes_from_path(
+                                            &mut podcasts[index],
+                                            &data_path,
+                                        )
+                                        .await?;
+                                        // Redirects may be temporary, and queued
+                                        // episodes and credentials are keyed by the
+                                        // subscribed url, so keep it.
+                                        podcast.url = url.clone();
+                                        // The gain is a local setting the feed knows nothing about.
+                                        podcast.gain_db = podcasts[index].gain_db;
+                                        progress.new_episodes += merge_episode_state(
+                                            &mut podcast,
+                                            &podcasts[index],
+                                            &data_path,
+                                            &config.filename_template,
+                                        );
+                                        save_podcast_info_to_path(&podcast, &data_path).await?;
+                                        podcasts[index] = podcast;
+
+                                        if let Some(key) = selected_key {
+                                            let podcast = &podcasts[index];
+                                            select_episode_index(
+                                                &mut episode_list_table_state,
+                                                &filter_episodes(podcast, &episode_filter),
+                                                podcast
+                                                    .episodes
+                                                    .iter()
+                                                    .position(|episode| episode.key() == key),
+                                            );
+                                        }
+                                        if let Some((podcast_index, key)) = inbox_key {
+                                            inbox_table_state.select(
+                                                inbox_episodes(&podcasts, is_inbox_favorites)
+                                                    .iter()
+                                                    .position(|&(p, e)| {
+                                                        p == podcast_index
+                                                            && podcasts[p].episodes[e].key() == key
+                                                    }),
+                                            );
+                                        }
+                                    }
+                                }
+                                Err(err) => progress.failed.push(format!("{url}: {err}")),
+                            }
+                        }
+                    },
+                    WorkerEvent::SearchFinished { term, result } => {
+                        if pending_search.as_ref() != Some(&term) {
+                            continue;
+                        }
+                        pending_search = None;
+
+                        search_results = result?;
+                        search_results_state.select(if search_results.is_empty() {
+                            None
+                        } else {
+                            Some(0)
+                        });
+                        if search_results.is_empty() {
+                            status_message = Some(format!("No podcasts found for {term}"));
+                        }
+                    }
+                    WorkerEvent::DownloadProgress {
+                        episode_url,
+                        received,
+                        total_len,
+                    } => {
+                        download_progress.insert(episode_url, (received, total_len));
+                    }
+                    WorkerEvent::EpisodeDownloaded {
+                        episode_url,
+                        title,
+                        result,
+                    } => {
+                        download_progress.remove(&episode_url);
+                        is_cache_changed = true;
+                        if let Some(batch) = &mut download_batch
+                            && batch.in_flight.remove(&episode_url)
+                        {
+                            batch.progress.completed += 1;
+                            if let Err(err) = result {
+                                batch.progress.failed.push(format!("{title}: {err}"));
+                            }
+                            batch.send_next(&worker, config.max_concurrent_downloads)?;
+                            continue;
+                        }
+                        pending_downloads -= 1;
+                        result?;
+                        status_message = Some(format!("Downloaded {title}"));
+                    }
+                    WorkerEvent::EpisodeExported {
+                        episode_url,
+                        title,
+                        result,
+                    } => {
+                        download_progress.remove(&episode_url);
+                        is_cache_changed = true;
+                        pending_exports -= 1;
+                        status_message = Some(match result {
+                            Ok(destination) => {
+                                format!("Exported {title} to {}", destination.display())
+                            }
+                            Err(err) => format!("Couldn't export {title}: {err}"),
+                        });
+                    }
+                    WorkerEvent::EpisodeLoaded {
+                        episode_url,
+                        result,
+                    } => {
+                        download_progress.remove(&episode_url);
+                        is_cache_changed = true;
+                        if loading_episode
+                            .as_ref()
+                            .is_none_or(|(loading_url, _)| *loading_url != episode_url)
+                        {
+                            if let Some(preload_state) = &mut preload
+                                && preload_state.episode_url() == episode_url
+                            {
+                                *preload_state = match result {
+                                    Ok(audio) => Preload::Ready(episode_url, audio),
+                                    // The next episode is requested again once the
+                                    // current one ends, which reports the error.
+                                    Err(err) => {
+                                        status_message =
+                                            Some(format!("Couldn't load the next episode: {err}"));
+                                        Preload::Failed(episode_url)
+                                    }
+                                };
+                            }
+                            continue;
+                        }
+                        loading_episode = None;
+
+                        let audio = result?;
+                        reset_finished_position(
+                            &mut podcasts,
+                            &episode_url,
+                            &audio,
+                            &mut stats,
+                            &data_path,
+                        )
+                        .await?;
+                        if let Some((podcast, episode)) = find_episode(&podcasts, &episode_url) {
+                            player = Some(start_episode(
+                                podcast,
+                                episode,
+                                audio,
+                                stream_handle.mixer(),
+                                &config,
+                                None,
+                            ));
+                            request_chapters(&worker, episode)?;
+                        }
+                    }
+                    WorkerEvent::TranscriptFetched {
+                        episode_url,
+                        result,
+                    } => {
+                        if pending_transcript.as_ref() != Some(&episode_url) {
+                            continue;
+                        }
+                        pending_transcript = None;
+
+                        let text = result?;
+                        if let Some((_, episode)) = find_episode(&podcasts, &episode_url)
+                            && let Some(link) = &episode.transcript
+                        {
+                            transcript_view = Some(TranscriptView {
+                                title: episode.title.clone(),
+                                mime_type: link.mime_type.clone(),
+                                text,
+                                with_timestamps: false,
+                            });
+                            info_scroll = InfoScroll::default();
+                            view_stack.push(ViewKind::Transcript);
+                        }
+                    }
+                    WorkerEvent::EpisodeSizeFetched {
+                        episode_url,
+                        result,
+                    } => {
+                        pending_episode_sizes.remove(&episode_url);
+                        // The size is only a hint, so a failed request just
+                        // leaves it out and is retried next time.
+                        if let Ok(size) = result {
+                            episode_sizes.insert(episode_url, size);
+                        }
+                    }
+                    WorkerEvent::ArtworkFetched { title, result } => {
+                        // Artwork is decoration, so it only reports through the
+                        // status line and is tried again on the next update.
+                        if let Err(err) = result {
+                            status_message =
+                                Some(format!("Couldn't download artwork for {title}: {err}"));
+                        }
+                    }
+                    WorkerEvent::ChaptersFetched {
+                        episode_url,
+                        result,
+                    } => {
+                        // Chapters are extra, so a broken document shouldn't
+                        // interrupt playback with an error popup.
+                        let chapters = match result {
+                            Ok(chapters) => chapters,
+                            Err(err) => {
+                                status_message = Some(format!("Couldn't load chapters: {err}"));
+                                continue;
+                            }
+                        };
+                        if let Some(podcast) = podcasts.iter_mut().find(|podcast| {
+                            podcast
+                                .episodes
+                                .iter()
+                                .any(|episode| episode.url == episode_url)
+                        }) {
+                            for episode in &mut podcast.episodes {
+                                if episode.url == episode_url {
+                                    episode.chapters = chapters.clone();
+                                }
+                            }
+                            save_podcast_info_to_path(podcast, &data_path).await?;
+                        }
+                    }
+                }
+            }
+
+            if let Some(progress) = import_progress.take_if(|progress| progress.is_done()) {
+                status_message = Some(format!(
+                    "Imported {} podcasts, skipped {} duplicates, {} failed",
+                    progress.completed - progress.failed.len(),
+                    progress.skipped,
+                    progress.failed.len()
+                ));
+            }
+            if is_cache_changed {
+                is_cache_changed = false;
+                if let Some(limit_mb) = config.cache_limit_mb {
+                    let evicted = evict_audio_cache(
+                        &mut podcasts,
+                        &data_path,
+                        &config.filename_template,
+                        limit_mb * 1024 * 1024,
+                        player
+                            .as_ref()
+                            .map(|player_state| player_state.episode_url.as_str()),
+                    )
+                    .await?;
+                    if evicted > 0 {
+                        status_message = Some(format!(
+                            "Removed {evicted} played downloads to fit the cache"
+                        ));
+                    }
+                }
+                cache_size = audio_cache_size_in_path(&data_path);
+                if episode_filter.downloads != DownloadFilter::All
+                    && let Some(podcast) = podcasts.get(current_podcast)
+                {
+                    let selected_index = selected_episode_index(
+                        &episode_list_table_state,
+                        &filter_episodes(podcast, &episode_filter),
+                    );
+                    episode_filter.downloaded =
+                        downloaded_episode_urls(&podcasts, &data_path, &config.filename_template);
+                    select_episode_index(
+                        &mut episode_list_table_state,
+                        &filter_episodes(podcast, &episode_filter),
+                        selected_index,
+                    );
+                }
+            }
+            if let Some(batch) = download_batch.take_if(|batch| batch.progress.is_done()) {
+                let progress = batch.progress;
+                status_message = Some(format!(
+                    "{} downloaded, {} failed, skipped {} already on disk",
+                    progress.total - progress.failed.len(),
+                    progress.failed.len(),
+                    progress.skipped
+                ));
+                if !progress.failed.is_empty() {
+                    failure_report = Some(FailureReport {
+                        title: "Failed downloads".to_string(),
+                        failures: progress.failed,
+                    });
+                    info_scroll = InfoScroll::default();
+                    view_stack.push(ViewKind::Failures);
+                }
+            }
+            if let Some(progress) = update_progress.take_if(|progress| progress.is_done()) {
+                status_message = Some(format!(
+                    "{} updated, {} failed, {} new episodes",
+                    progress.total - progress.failed.len(),
+                    progress.failed.len(),
+                    progress.new_episodes
+                ));
+                if !progress.failed.is_empty() {
+                    failure_report = Some(FailureReport {
+                        title: "Failed updates".to_string(),
+                        failures: progress.failed,
+                    });
+                    info_scroll = InfoScroll::default();
+                    view_stack.push(ViewKind::Failures);
+                }
+            }
+            // Background refreshes only report through the status line so they
+            // never pull the user out of what they're doing.
+            if let Some(progress) = auto_update_progress.take_if(|progress| progress.is_done())
+                && progress.new_episodes > 0
+            {
+                status_message = Some(format!("{} new episodes", progress.new_episodes));
+            }
+            if let Some(deadline) = next_auto_update
+                && Instant::now() >= deadline
+            {
+                next_auto_update = auto_update_interval.map(|interval| deadline + interval);
+                if update_progress.is_none() && auto_update_progress.is_none() {
+                    request_feed_updates(
+                        &worker,
+                        &podcasts,
+                        &feed_credentials,
+                        FeedRequestKind::AutoUpdate,
+                    )?;
+                    auto_update_progress =
+                        Some(BatchProgress::new("Refreshing", podcasts.len(), 0));
+                }
+            }
+
+            if let Some(player_state) = &mut player
+                && let (Some(loop_start), Some(loop_end)) =
+                    (player_state.loop_start, player_state.loop_end)
+                && player_state.position() >= loop_end
+                && let Err(err) = player_state.sink.try_seek(loop_start)
+            {
+                player_state.loop_start = None;
+                player_state.loop_end = None;
+                status_message = Some(format!("Couldn't repeat: {err}"));
+            }
+
+            if let Some(deadline) = sleep_deadline
+                && Instant::now() >= deadline
+            {
+                sleep_deadline = None;
+                if let Some(player_state) = &player
+                    && !player_state.sink.is_paused()
+                {
+                    player_state.sink.pause();
+                    save_playback_position(player_state, &mut podcasts, &mut stats, &data_path)
+                        .await?;
+                }
+            }
+
+            // Pausing, stopping or switching episodes cuts a fade short.
+            if let Some(fading) = &fading_episode
+                && (fading.player_state.sink.empty()
+                    || player.as_ref().is_none_or(|player_state| {
+                        player_state.episode_url != fading.next_episode_url
+                            || player_state.sink.is_paused()
+                    }))
+                && let Some(fading) = fading_episode.take()
+            {
+                fading.player_state.sink.stop();
+                save_playback_position(&fading.player_state, &mut podcasts, &mut stats, &data_path)
+                    .await?;
+            }
+
+            let crossfade = Duration::from_secs(config.crossfade_secs);
+            let mut crossfade_into = None;
+            if !crossfade.is_zero()
+                && let Some(player_state) = &player
+                && player_state.loop_end.is_none()
+                && let Some(duration) = player_state.duration
+                && let remaining = duration.saturating_sub(player_state.position())
+                && remaining <= crossfade + PRELOAD_LEAD
+                && let Some((podcast_index, episode_index)) =
+                    upcoming_episode(&queue, &podcasts, &config, &episode_filter, player_state)
+            {
+                let podcast = &podcasts[podcast_index];
+                let episode = &podcast.episodes[episode_index];
+                if preload
+                    .as_ref()
+                    .is_none_or(|preload| preload.episode_url() != episode.url)
+                {
+                    request_episode(&worker, podcast, episode, &config)?;
+                    preload = Some(Preload::Loading(episode.url.clone()));
+                } else if remaining <= crossfade
+                    && !player_state.sink.is_paused()
+                    && matches!(preload, Some(Preload::Ready(..)))
+                {
+                    crossfade_into = Some((podcast_index, episode_index));
+                }
+            }
+            if let Some((podcast_index, episode_index)) = crossfade_into
+                && let Some(Preload::Ready(episode_url, audio)) = preload.take()
+                && let Some(player_state) = player.take()
+            {
+                if let Some(fading) = fading_episode.take() {
+                    fading.player_state.sink.stop();
+                    save_playback_position(
+                        &fading.player_state,
+                        &mut podcasts,
+                        &mut stats,
+                        &data_path,
+                    )
+                    .await?;
+                }
+                if queue
+                    .iter()
+                    .any(|queued| find_queued_episode(queued, &podcasts).is_some())
+                {
+                    _ = pop_queued_episode(&mut queue, &podcasts);
+                }
+
+                player_state.fader.start(crossfade);
+                reset_finished_position(
+                    &mut podcasts,
+                    &episode_url,
+                    &audio,
+                    &mut stats,
+                    &data_path,
+                )
+                .await?;
+                let podcast = &podcasts[podcast_index];
+                let episode = &podcast.episodes[episode_index];
+                player = Some(start_episode(
+                    podcast,
+                    episode,
+                    audio,
+                    stream_handle.mixer(),
+                    &config,
+                    Some(crossfade),
+                ));
+                request_chapters(&worker, episode)?;
+                fading_episode = Some(FadingEpisode {
+                    player_state,
+                    next_episode_url: episode.url.clone(),
+                });
+
+                if current_podcast == podcast_index {
+                    select_episode_index(
+                        &mut episode_list_table_state,
+                        &filter_episodes(podcast, &episode_filter),
+                        Some(episode_index),
+                    );
+                }
+            }
+
+            if let Some(player_state) = &player
+                && player_state.sink.empty()
+            {
+                save_playback_position(player_state, &mut podcasts, &mut stats, &data_path).await?;
+
+                let next_episode = if let Some(queued) = pop_queued_episode(&mut queue, &podcasts) {
+                    Some(queued)
+                } else if config.auto_advance {
+                    auto_advance_episode(&podcasts, &episode_filter, player_state)
+                } else {
+                    None
+                };
+
+                player = None;
+                if let Some((podcast_index, episode_index)) = next_episode {
+                    let podcast = &podcasts[podcast_index];
+                    let episode = &podcast.episodes[episode_index];
+                    match preload.take() {
+                        // Ending earlier than its reported duration skips the
+                        // crossfade, but a preloaded episode still starts
+                        // without a gap.
+                        Some(Preload::Ready(episode_url, audio)) if episode_url == episode.url => {
+                            reset_finished_position(
+                                &mut podcasts,
+                                &episode_url,
+                                &audio,
+                                &mut stats,
+                                &data_path,
+                            )
+                            .await?;
+                            let podcast = &podcasts[podcast_index];
+                            let episode = &podcast.episodes[episode_index];
+                            player = Some(start_episode(
+                                podcast,
+                                episode,
+                                audio,
+                                stream_handle.mixer(),
+                                &config,
+                                None,
+                            ));
+                            request_chapters(&worker, episode)?;
+                        }
+                        Some(Preload::Loading(episode_url)) if episode_url == episode.url => {
+                            loading_episode = Some((
+                                episode_url,
+                                format!("{} / {}", podcast.title, episode.title),
+                            ));
+                        }
+                        _ => {
+                            loading_episode =
+                                Some(request_episode(&worker, podcast, episode, &config)?);
+                        }
+                    }
+
+                    if current_podcast == podcast_index {
+                        select_episode_index(
+                            &mut episode_list_table_state,
+                            &filter_episodes(&podcasts[podcast_index], &episode_filter),
+                            Some(episode_index),
+                        );
+                    }
+                }
+            }
+
+            Ok(())
+        }
+        .await;
+        if let Err(err) = tick_result {
+            error_info_popup_state = ErrorInfoPopupState::new(err.to_string());
+            view_stack.push(ViewKind::ErrorInfo);
+        }
+
+        let is_loading = loading_episode.is_some()
+            || update_progress.is_some()
+            || import_progress.is_some()
+            || pending_adds > 0
+            || pending_downloads > 0
+            || pending_exports > 0
+            || download_batch.is_some()
+            || pending_search.is_some()
+            || pending_transcript.is_some();
+        let spinner = if is_loading {
+            SPINNER_FRAMES[frame_count % SPINNER_FRAMES.len()]
+        } else {
+            ""
+        };
+
+        // Leave views whose podcast or episode went away instead of indexing
+        // past the end of the lists.
+        if podcasts.get(current_podcast).is_none() {
+            view_stack.retain(|view_kind| !view_kind.shows_current_podcast());
+        }
+        if matches!(view_stack.last(), Some(ViewKind::EpisodeInfo))
+            && selected_episode_index(
+                &episode_list_table_state,
+                &filter_episodes(&podcasts[current_podcast], &episode_filter),
+            )
+            .is_none()
+        {
+            _ = view_stack.pop();
+        }
+
+        let draw_result = terminal.draw(|frame| {
             let main_layout = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(1),
-                    Constraint::Fill(1),
-                    Constraint::Length(5),
-                ])
+                .constraints(if config.compact {
+                    [
+                        Constraint::Length(0),
+                        Constraint::Fill(1),
+                        Constraint::Length(1),
+                        Constraint::Length(0),
+                    ]
+                } else {
+                    [
+                        Constraint::Length(1),
+                        Constraint::Fill(1),
+                        Constraint::Length(6),
+                        Constraint::Length(1),
+                    ]
+                })
                 .split(frame.area());
+            list_area = main_layout[1];
+            player_area = main_layout[2];
 
-            frame.render_widget(
-                Paragraph::new(Span::styled("Teapod", title_style)),
-                main_layout[0],
-            );
+            let activity = [
+                update_progress.as_ref(),
+                import_progress.as_ref(),
+                download_batch.as_ref().map(|batch| &batch.progress),
+            ]
+            .into_iter()
+            .flatten()
+            .map(BatchProgress::label)
+            .chain((pending_adds > 0).then(|| "Adding podcast…".to_string()))
+            .chain(download_label(pending_downloads, &download_progress))
+            .chain(status_message.clone())
+            .collect::<Vec<_>>()
+            .join(" ");
+            let activity = if spinner.is_empty() && activity.is_empty() {
+                String::new()
+            } else {
+                format!("{spinner} {activity}")
+            };
+            // Compact mode has no footer, so feedback shares the player's line.
+            let (compact_player_area, activity_area) = if !config.compact {
+                frame.render_widget(
+                    Paragraph::new(Span::styled("Teapod", theme.title)),
+                    main_layout[0],
+                );
+                (main_layout[2], main_layout[3])
+            } else if activity.is_empty() {
+                (main_layout[2], Rect::default())
+            } else {
+                let activity_width =
+                    (Line::raw(activity.as_str()).width() as u16).min(main_layout[2].width / 2);
+                let [player_line, activity_line] = Layout::horizontal([
+                    Constraint::Fill(1),
+                    Constraint::Length(activity_width),
+                ])
+                .spacing(1)
+                .areas(main_layout[2]);
+                (player_line, activity_line)
+            };
+            frame.render_widget(Paragraph::new(activity), activity_area);
 
             match view_stack.last() {
                 Some(view_kind) => match view_kind {
                     ViewKind::PodcastInfo => {
-                        let podcast = &podcasts[podcast_list_state.selected().unwrap()];
-                        frame.render_widget(
-                            Paragraph::new(vec![
+                        let podcast = &podcasts[current_podcast];
+                        let mut info_area = main_layout[1];
+                        if let Some(artwork) = &podcast_artwork {
+                            let [artwork_area, rest] = Layout::horizontal([
+                                Constraint::Length(ARTWORK_WIDTH + 2),
+                                Constraint::Fill(1),
+                            ])
+                            .areas(main_layout[1]);
+                            let artwork_block = Block::bordered().border_style(theme.border);
+                            frame.render_widget(
+                                Artwork::new(artwork),
+                                artwork_block.inner(artwork_area),
+                            );
+                            frame.render_widget(artwork_block, artwork_area);
+                            info_area = rest;
+                        }
+                        render_info_paragraph(
+                            frame,
+                            info_area,
+                            [
                                 Line::from(vec![
-                                    Span::styled("Description: ", title_style),
-                                    Span::raw(podcast.description.as_str()),
+                                    Span::styled("Url: ", theme.title),
+                                    Span::raw(podcast.url.as_str()),
                                 ]),
                                 Line::from(vec![
-                                    Span::styled("Url: ", title_style),
-                                    Span::raw(podcast.url.as_str()),
+                                    Span::styled("Downloads: ", theme.title),
+                                    Span::raw(format_file_size(podcast_audio_size)),
                                 ]),
-                            ])
-                            .block(Block::bordered().title(Line::from(vec![
-                                Span::styled(podcast.title.as_str(), title_style),
-                                Span::styled(" / Info", title_style),
-                            ])))
-                            .wrap(Wrap { trim: true }),
+                                Line::from(vec![
+                                    Span::styled("Gain: ", theme.title),
+                                    Span::raw(format_gain(podcast.gain_db)),
+                                ]),
+                            ]
+                            .into_iter()
+                            .chain(labeled_lines(
+                                "Description: ",
+                                &podcast.description,
+                                theme.title,
+                            ))
+                            .collect(),
+                            Block::bordered()
+                                .border_style(theme.border)
+                                .title(Line::from(vec![
+                                    Span::styled(podcast.title.as_str(), theme.title),
+                                    Span::styled(" / Info", theme.title),
+                                ]))
+                                .title_bottom("[/]: lower/raise gain"),
+                            &mut info_scroll,
+                        );
+                    }
+                    ViewKind::Failures => {
+                        if let Some(report) = &failure_report {
+                            render_info_paragraph(
+                                frame,
+                                main_layout[1],
+                                report
+                                    .failures
+                                    .iter()
+                                    .map(|failure| Line::raw(failure.as_str()))
+                                    .collect(),
+                                Block::bordered()
+                                    .border_style(theme.error_border)
+                                    .title(Span::styled(report.title.as_str(), theme.title)),
+                                &mut info_scroll,
+                            );
+                        }
+                    }
+                    ViewKind::Stats => {
+                        let mut podcast_stats = stats.podcasts.values().collect::<Vec<_>>();
+                        podcast_stats.sort_by_key(|podcast_stats| {
+                            std::cmp::Reverse(podcast_stats.listening_secs)
+                        });
+                        render_info_paragraph(
+                            frame,
                             main_layout[1],
+                            [
+                                Line::from(vec![
+                                    Span::styled("Episodes played: ", theme.title),
+                                    Span::raw(stats.episodes_played.to_string()),
+                                ]),
+                                Line::from(vec![
+                                    Span::styled("Listening time: ", theme.title),
+                                    Span::raw(format_listening_time(stats.listening_secs)),
+                                ]),
+                                Line::default(),
+                            ]
+                            .into_iter()
+                            .chain(podcast_stats.into_iter().map(|podcast_stats| {
+                                Line::from(vec![
+                                    Span::styled(
+                                        format!("{}: ", podcast_stats.title),
+                                        theme.title,
+                                    ),
+                                    Span::raw(format!(
+                                        "{} episodes, {}",
+                                        podcast_stats.episodes_played,
+                                        format_listening_time(podcast_stats.listening_secs)
+                                    )),
+                                ])
+                            }))
+                            .collect(),
+                            Block::bordered()
+                                .border_style(theme.border)
+                                .title(Span::styled("Listening stats", theme.title))
+                                .title_bottom(format!("Saved to {}", data_path.join(STATS_FILE).display())),
+                            &mut info_scroll,
                         );
                     }
-                    ViewKind::AddPodcast => frame.render_widget(
-                        Paragraph::new(Line::from(vec![
-                            Span::styled("Podcast url: ", title_style),
-                            Span::raw(add_podcast_url.as_str()),
-                        ]))
-                        .block(Block::bordered().title(Span::styled("Add a podcast", title_style)))
+                    ViewKind::AddPodcast => {
+                        let block = Block::bordered()
+                            .border_style(theme.border)
+                            .title(Span::styled("Add a podcast", theme.title))
+                            .title_bottom("Tab: next field, Ctrl-v: paste, Ctrl-f: search by name");
+                        let fields_layout = Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([Constraint::Length(1); 3])
+                            .split(block.inner(main_layout[1]));
+                        frame.render_widget(block, main_layout[1]);
+                        frame.render_stateful_widget(
+                            TextInput::new("Podcast url: ", theme.title)
+                                .show_cursor(add_podcast_field == AddPodcastField::Url),
+                            fields_layout[0],
+                            &mut add_podcast_input,
+                        );
+                        frame.render_stateful_widget(
+                            TextInput::new("Username:    ", theme.title)
+                                .show_cursor(add_podcast_field == AddPodcastField::Username),
+                            fields_layout[1],
+                            &mut add_username_input,
+                        );
+                        frame.render_stateful_widget(
+                            TextInput::new("Password:    ", theme.title)
+                                .show_cursor(add_podcast_field == AddPodcastField::Password)
+                                .masked(true),
+                            fields_layout[2],
+                            &mut add_password_input,
+                        );
+                    }
+                    ViewKind::SearchPodcast => {
+                        let search_layout = Layout::default()
+                            .direction(Direction::Vertical)
+                            .constraints([Constraint::Length(3), Constraint::Fill(1)])
+                            .split(main_layout[1]);
+
+                        frame.render_stateful_widget(
+                            TextInput::new("Search: ", theme.title)
+                                .show_cursor(is_editing_search)
+                                .block(
+                                    Block::bordered()
+                                        .border_style(theme.border)
+                                        .title(Span::styled("Search podcasts", theme.title)),
+                                ),
+                            search_layout[0],
+                            &mut search_input,
+                        );
+                        frame.render_stateful_widget(
+                            List::new(
+                                search_results
+                                    .iter()
+                                    .map(|result| {
+                                        Line::from(vec![
+                                            Span::raw(result.title.as_str()),
+                                            Span::raw(" - "),
+                                            Span::raw(result.author.as_str()).italic(),
+                                        ])
+                                    })
+                                    .collect::<Vec<_>>(),
+                            )
+                            .block(
+                                Block::bordered()
+                                    .border_style(theme.border)
+                                    .title(Span::styled("Results", theme.title))
+                                    .title_bottom(if is_editing_search {
+                                        "Enter: search, Esc: cancel"
+                                    } else {
+                                        "Enter: subscribe, /: edit search, Esc: back"
+                                    }),
+                            )
+                            .highlight_style(theme.highlight),
+                            search_layout[1],
+                            &mut search_results_state,
+                        );
+                    }
+                    ViewKind::ErrorInfo => frame.render_stateful_widget(
+                        ErrorInfoPopup::new(theme),
+                        main_layout[1],
+                        &mut error_info_popup_state,
+                    ),
+                    ViewKind::Confirm(_) => frame.render_stateful_widget(
+                        ConfirmPopup::new(theme),
+                        main_layout[1],
+                        &mut confirm_popup_state,
+                    ),
+                    ViewKind::ExportOpml => frame.render_stateful_widget(
+                        TextInput::new("Output file: ", theme.title).block(
+                            Block::bordered()
+                                .border_style(theme.border)
+                                .title(Span::styled("Export OPML", theme.title)),
+                        ),
+                        main_layout[1],
+                        &mut opml_path_input,
+                    ),
+                    ViewKind::ImportSubscriptions => frame.render_stateful_widget(
+                        TextInput::new("File: ", theme.title).block(
+                            Block::bordered()
+                                .border_style(theme.border)
+                                .title(Span::styled("Import subscriptions", theme.title))
+                                .title_bottom(".opml file, or one feed url per line"),
+                        ),
+                        main_layout[1],
+                        &mut opml_path_input,
+                    ),
+                    ViewKind::ExportEpisode(_) => frame.render_stateful_widget(
+                        TextInput::new("Destination: ", theme.title).block(
+                            Block::bordered()
+                                .border_style(theme.border)
+                                .title(Span::styled("Export episode", theme.title))
+                                .title_bottom("A file path, or a directory to keep the episode's name"),
+                        ),
+                        main_layout[1],
+                        &mut export_path_input,
+                    ),
+                    ViewKind::GoToTime => frame.render_stateful_widget(
+                        TextInput::new("Time: ", theme.title).block(
+                            Block::bordered()
+                                .border_style(theme.border)
+                                .title(Span::styled("Go to time", theme.title))
+                                .title_bottom("MM:SS or HH:MM:SS"),
+                        ),
+                        main_layout[1],
+                        &mut go_to_time_input,
+                    ),
+                    ViewKind::SleepTimer => frame.render_widget(
+                        Paragraph::new(vec![
+                            Line::from(vec![
+                                Span::styled("Minutes: ", theme.title),
+                                Span::raw(sleep_timer_minutes.as_str()),
+                            ]),
+                            Line::from("Enter 0 to cancel the timer."),
+                        ])
+                        .block(
+                            Block::bordered()
+                                .border_style(theme.border)
+                                .title(Span::styled("Sleep timer", theme.title)),
+                        )
                         .wrap(Wrap { trim: true }),
                         main_layout[1],
                     ),
                     ViewKind::EpisodeList => {
-                        let podcast = &podcasts[podcast_list_state.selected().unwrap()];
-                        if episode_list_table_state.selected().is_none()
-                            && podcast.episodes.len() > 0
-                        {
-                            episode_list_table_state.select_first();
-                        }
+                        let podcast = &podcasts[current_podcast];
+                        let episode_indices = filter_episodes(podcast, &episode_filter);
+                        episode_list_table_state.select(clamp_selection(
+                            episode_list_table_state.selected(),
+                            episode_indices.len(),
+                        ));
+
+                        // Only rows inside the viewport are built, so long feeds cost
+                        // no more per frame than short ones. The border and header
+                        // take three rows.
+                        let visible_rows = main_layout[1].height.saturating_sub(3) as usize;
+                        let selected = episode_list_table_state.selected();
+                        episode_list_offset = scroll_window(
+                            episode_list_offset,
+                            selected.unwrap_or_default(),
+                            visible_rows,
+                            episode_indices.len(),
+                        );
+                        let window_end =
+                            (episode_list_offset + visible_rows).min(episode_indices.len());
+                        let mut window_state = TableState::default()
+                            .with_selected(selected.map(|selected| selected - episode_list_offset));
+                        let playing_url = player
+                            .as_ref()
+                            .filter(|player_state| player_state.podcast_url == podcast.url)
+                            .map(|player_state| player_state.episode_url.as_str());
 
                         frame.render_stateful_widget(
                             Table::new(
-                                podcast
-                                    .episodes
+                                episode_indices[episode_list_offset..window_end]
                                     .iter()
-                                    .map(|episode| {
+                                    .map(|&index| {
+                                        let episode = &podcast.episodes[index];
                                         let is_downloaded = check_podcast_audio_in_path(
-                                            podcast, episode, &data_path,
+                                            podcast,
+                                            episode,
+                                            &data_path,
+                                            &config.filename_template,
+                                        );
+
+                                        let is_playing = playing_url == Some(episode.url.as_str());
+                                        let mut title = episode_title_line(episode);
+                                        title.spans.insert(
+                                            0,
+                                            Span::raw(if is_playing {
+                                                "▶ "
+                                            } else if episode.played {
+                                                "✓ "
+                                            } else {
+                                                "  "
+                                            }),
                                         );
 
                                         Row::new(vec![
-                                            episode.title.as_str(),
-                                            episode.pub_date.as_str(),
-                                            if is_downloaded { "Yes" } else { "No" },
+                                            Cell::from(title),
+                                            Cell::from(format_episode_date(episode, date_format)),
+                                            Cell::from(
+                                                episode
+                                                    .duration
+                                                    .map(format_audio_duration)
+                                                    .unwrap_or_default(),
+                                            ),
+                                            Cell::from(if is_downloaded { "Yes" } else { "No" }),
                                         ])
+                                        .style(if is_playing {
+                                            theme.now_playing
+                                        } else if episode.played {
+                                            theme.played
+                                        } else {
+                                            Style::new()
+                                        })
                                     })
                                     .collect::<Vec<_>>(),
                                 [
                                     Constraint::Fill(1),
+                                    Constraint::Length(date_width),
+                                    Constraint::Length(8),
                                     Constraint::Length(10),
-                                    Constraint::Length(10),
                                 ],
                             )
                             .header(
-                                Row::new(vec!["Title", "Date", "Downloaded"])
-                                    .style(table_header_style),
+                                Row::new(vec!["Title", "Date", "Length", "Downloaded"])
+                                    .style(theme.table_header),
+                            )
+                            .block({
+                                let block = Block::bordered()
+                                    .border_style(theme.border)
+                                    .title(Line::from(vec![
+                                        Span::styled(podcast.title.as_str(), theme.title),
+                                        Span::styled(" / Episodes", theme.title),
+                                        Span::raw(episode_filter.label()),
+                                    ]))
+                                    .title_bottom(position_label(
+                                        selected,
+                                        episode_indices.len(),
+                                    ));
+                                if is_searching || !episode_filter.query.is_empty() {
+                                    block.title_bottom(format!("/{}", episode_filter.query))
+                                } else {
+                                    block
+                                }
+                            })
+                            .row_highlight_style(theme.highlight),
+                            main_layout[1],
+                            &mut window_state,
+                        );
+                        if podcast.episodes.is_empty() {
+                            render_empty_hint(
+                                frame,
+                                main_layout[1],
+                                "No episodes yet — press u on the podcast list to update",
+                            );
+                        } else if episode_indices.is_empty() {
+                            render_empty_hint(
+                                frame,
+                                main_layout[1],
+                                "No episodes match the current filter",
+                            );
+                        }
+                        if episode_indices.len() > visible_rows {
+                            frame.render_stateful_widget(
+                                Scrollbar::new(ScrollbarOrientation::VerticalRight),
+                                main_layout[1],
+                                &mut ScrollbarState::new(episode_indices.len() - visible_rows + 1)
+                                    .position(episode_list_offset),
+                            );
+                        }
+                    }
+                    ViewKind::EpisodeInfo => {
+                        let podcast = &podcasts[current_podcast];
+                        if let Some(episode_index) = selected_episode_index(
+                            &episode_list_table_state,
+                            &filter_episodes(podcast, &episode_filter),
+                        ) {
+                            let episode = &podcast.episodes[episode_index];
+
+                            render_info_paragraph(
+                                frame,
+                                main_layout[1],
+                                vec![
+                                    Line::from(vec![
+                                        Span::styled("Date: ", theme.title),
+                                        Span::raw(
+                                            episode
+                                                .published_date()
+                                                .map(|date| {
+                                                    date.format(
+                                                        date_format.unwrap_or(ISO_DATE_FORMAT),
+                                                    )
+                                                    .to_string()
+                                                })
+                                                .unwrap_or_else(|| {
+                                                    if episode.pub_date.is_empty() {
+                                                        "Unknown".to_string()
+                                                    } else {
+                                                        episode.pub_date.clone()
+                                                    }
+                                                }),
+                                        ),
+                                    ]),
+                                    Line::from(vec![
+                                        Span::styled("Length: ", theme.title),
+                                        Span::raw(
+                                            episode
+                                                .duration
+                                                .map(format_audio_duration)
+                                                .unwrap_or_else(|| "Unknown".to_string()),
+                                        ),
+                                    ]),
+                                ]
+                                .into_iter()
+                                .chain(
+                                    if pending_episode_sizes.contains(&episode.url) {
+                                        Some("Checking…".to_string())
+                                    } else {
+                                        episode_sizes.get(&episode.url).map(|size| {
+                                            size.map(format_file_size)
+                                                .unwrap_or_else(|| "Unknown".to_string())
+                                        })
+                                    }
+                                    .map(|size| {
+                                        Line::from(vec![
+                                            Span::styled("Size: ", theme.title),
+                                            Span::raw(size),
+                                        ])
+                                    }),
+                                )
+                                .chain(episode.numbering().map(|numbering| {
+                                    Line::from(vec![
+                                        Span::styled("Episode: ", theme.title),
+                                        Span::raw(numbering),
+                                    ])
+                                }))
+                                .chain(labeled_lines(
+                                    "Description: ",
+                                    &episode.description,
+                                    theme.title,
+                                ))
+                                .collect(),
+                                Block::bordered()
+                                    .border_style(theme.border)
+                                    .title(Line::from(vec![
+                                        Span::styled(podcast.title.as_str(), theme.title),
+                                        Span::raw(" / "),
+                                        Span::styled(episode.title.as_str(), theme.title),
+                                        Span::styled(
+                                            if episode.explicit { " [E]" } else { "" },
+                                            Style::new().bold(),
+                                        ),
+                                        Span::styled(" / Info", theme.title),
+                                    ])),
+                                &mut info_scroll,
+                            );
+                        }
+                    }
+                    ViewKind::Transcript => {
+                        if let Some(transcript) = &transcript_view {
+                            let text = format_transcript(
+                                &transcript.text,
+                                &transcript.mime_type,
+                                transcript.with_timestamps,
+                            );
+                            let mut block = Block::bordered()
+                                .border_style(theme.border)
+                                .title(Line::from(vec![
+                                    Span::styled(transcript.title.as_str(), theme.title),
+                                    Span::styled(" / Transcript", theme.title),
+                                ]));
+                            if has_timestamps(&transcript.mime_type) {
+                                block = block.title_bottom(if transcript.with_timestamps {
+                                    "t: hide timestamps"
+                                } else {
+                                    "t: show timestamps"
+                                });
+                            }
+                            render_info_paragraph(
+                                frame,
+                                main_layout[1],
+                                text.lines().map(Line::from).collect(),
+                                block,
+                                &mut info_scroll,
+                            );
+                        }
+                    }
+                    ViewKind::Chapters => {
+                        let chapters = player
+                            .as_ref()
+                            .and_then(|player_state| {
+                                find_episode(&podcasts, &player_state.episode_url)
+                            })
+                            .map(|(_, episode)| episode.chapters.as_slice())
+                            .unwrap_or_default();
+                        frame.render_stateful_widget(
+                            List::new(
+                                chapters
+                                    .iter()
+                                    .map(|chapter| {
+                                        Line::from(vec![
+                                            Span::styled(
+                                                format!("{} ", format_audio_duration(chapter.start)),
+                                                Style::new().dim(),
+                                            ),
+                                            Span::raw(chapter.title.as_str()),
+                                        ])
+                                    })
+                                    .collect::<Vec<_>>(),
+                            )
+                            .block(
+                                Block::bordered()
+                                    .border_style(theme.border)
+                                    .title(Span::styled("Chapters", theme.title))
+                                    .title_bottom("Enter: jump to chapter"),
+                            )
+                            .highlight_style(theme.highlight),
+                            main_layout[1],
+                            &mut chapter_list_state,
+                        );
+                    }
+                    ViewKind::OutputDevice => frame.render_stateful_widget(
+                        List::new(
+                            std::iter::once("System default")
+                                .chain(output_devices.iter().map(String::as_str))
+                                .collect::<Vec<_>>(),
+                        )
+                        .block(
+                            Block::bordered()
+                                .border_style(theme.border)
+                                .title(Span::styled("Output device", theme.title)),
+                        )
+                        .highlight_style(theme.highlight),
+                        main_layout[1],
+                        &mut output_device_state,
+                    ),
+                    ViewKind::Queue => {
+                        queue_list_state.select(clamp_selection(
+                            queue_list_state.selected(),
+                            queue.len(),
+                        ));
+
+                        frame.render_stateful_widget(
+                            List::new(
+                                queue
+                                    .iter()
+                                    .map(|queued| queued.title.as_str())
+                                    .collect::<Vec<_>>(),
+                            )
+                            .block(
+                                Block::bordered()
+                                    .border_style(theme.border)
+                                    .title(Span::styled(
+                                        format!("Queue ({})", queue.len()),
+                                        theme.title,
+                                    ))
+                                    .title_bottom("Enter: play now, d: remove, K/J: move"),
+                            )
+                            .highlight_style(theme.highlight),
+                            main_layout[1],
+                            &mut queue_list_state,
+                        );
+                    }
+                    ViewKind::Inbox => {
+                        let entries = inbox_episodes(&podcasts, is_inbox_favorites);
+                        inbox_table_state.select(clamp_selection(
+                            inbox_table_state.selected(),
+                            entries.len(),
+                        ));
+
+                        frame.render_stateful_widget(
+                            Table::new(
+                                entries
+                                    .iter()
+                                    .map(|&(podcast_index, episode_index)| {
+                                        let podcast = &podcasts[podcast_index];
+                                        let episode = &podcast.episodes[episode_index];
+                                        Row::new(vec![
+                                            Cell::from(podcast.title.as_str()),
+                                            Cell::from(episode_title_line(episode)),
+                                            Cell::from(format_episode_date(episode, date_format)),
+                                            Cell::from(
+                                                episode
+                                                    .duration
+                                                    .map(format_audio_duration)
+                                                    .unwrap_or_default(),
+                                            ),
+                                        ])
+                                    })
+                                    .collect::<Vec<_>>(),
+                                [
+                                    Constraint::Percentage(30),
+                                    Constraint::Fill(1),
+                                    Constraint::Length(date_width),
+                                    Constraint::Length(8),
+                                ],
+                            )
+                            .header(
+                                Row::new(vec!["Podcast", "Title", "Date", "Length"])
+                                    .style(theme.table_header),
+                            )
+                            .block(
+                                Block::bordered()
+                                    .border_style(theme.border)
+                                    .title(Span::styled(
+                                        if is_inbox_favorites {
+                                            "Favorites"
+                                        } else {
+                                            "New episodes"
+                                        },
+                                        theme.title,
+                                    ))
+                                    .title_bottom(
+                                        "Enter: play, e: enqueue, x: mark played, f: favorite, F: show favorites",
+                                    ),
                             )
-                            .block(Block::bordered().title(Line::from(vec![
-                                Span::styled(podcast.title.as_str(), title_style),
-                                Span::styled(" / Episodes", title_style),
-                            ])))
-                            .row_highlight_style(Style::new().reversed()),
-                            main_layout[1],
-                            &mut episode_list_table_state,
-                        );
-                    }
-                    ViewKind::EpisodeInfo => {
-                        let podcast = &podcasts[podcast_list_state.selected().unwrap()];
-                        let episode =
-                            &podcast.episodes[episode_list_table_state.selected().unwrap()];
-
-                        frame.render_widget(
-                            Paragraph::new(vec![Line::from(vec![
-                                Span::styled("Description: ", title_style),
-                                Span::raw(episode.description.as_str()),
-                            ])])
-                            .block(Block::bordered().title(Line::from(vec![
-                                Span::styled(podcast.title.as_str(), title_style),
-                                Span::raw(" / "),
-                                Span::styled(episode.title.as_str(), title_style),
-                                Span::styled(" / Info", title_style),
-                            ])))
-                            .wrap(Wrap { trim: true }),
+                            .row_highlight_style(theme.highlight),
                             main_layout[1],
+                            &mut inbox_table_state,
                         );
                     }
                 },
                 None => {
-                    if podcast_list_state.selected().is_none() && podcasts.len() > 0 {
-                        podcast_list_state.select_first();
-                    }
+                    let podcast_indices = filter_podcasts(&podcasts, &podcast_query);
+                    podcast_list_state.select(clamp_selection(
+                        podcast_list_state.selected(),
+                        podcast_indices.len(),
+                    ));
 
+                    let cache_label = match config.cache_limit_mb {
+                        Some(limit_mb) => format!(
+                            "Cache: {} / {}",
+                            format_file_size(cache_size),
+                            format_file_size(limit_mb * 1024 * 1024)
+                        ),
+                        None => format!("Cache: {}", format_file_size(cache_size)),
+                    };
+                    let mut podcast_list_block = Block::bordered()
+                        .border_style(theme.border)
+                        .title(Span::styled("Podcasts", theme.title))
+                        .title(Line::from(cache_label).right_aligned())
+                        .title_bottom(position_label(
+                            podcast_list_state.selected(),
+                            podcast_indices.len(),
+                        ));
+                    if is_searching || !podcast_query.is_empty() {
+                        podcast_list_block =
+                            podcast_list_block.title_bottom(format!("/{}", podcast_query));
+                    }
                     frame.render_stateful_widget(
                         List::new(
-                            podcasts
+                            podcast_indices
                                 .iter()
-                                .map(|podcast| podcast.title.as_str())
+                                .map(|&index| {
+                                    let podcast = &podcasts[index];
+                                    let counts = podcast.episode_counts();
+                                    let unplayed = counts.unplayed;
+                                    Line::from(vec![
+                                        Span::raw(podcast.title.as_str()),
+                                        Span::styled(
+                                            format!(" ({}", counts.total),
+                                            Style::new().dim(),
+                                        ),
+                                        if unplayed > 0 {
+                                            Span::styled(format!(", {unplayed} new"), theme.title)
+                                        } else {
+                                            Span::raw("")
+                                        },
+                                        Span::styled(")", Style::new().dim()),
+                                    ])
+                                })
                                 .collect::<Vec<_>>(),
                         )
-                        .block(Block::bordered().title(Span::styled("Podcasts", title_style)))
-                        .highlight_style(Style::new().reversed()),
+                        .block(podcast_list_block)
+                        .highlight_style(theme.highlight),
                         main_layout[1],
                         &mut podcast_list_state,
                     );
+                    if podcasts.is_empty() {
+                        render_empty_hint(
+                            frame,
+                            main_layout[1],
+                            "No podcasts yet — press a to add one",
+                        );
+                    } else if podcast_indices.is_empty() {
+                        render_empty_hint(frame, main_layout[1], "No podcasts match the search");
+                    }
                 }
             }
 
             if let Some(player_state) = &player {
+                let chapter_title = find_episode(&podcasts, &player_state.episode_url)
+                    .and_then(|(_, episode)| episode.chapter_at(player_state.position()))
+                    .map(|chapter| chapter.title.as_str());
                 let status = if player_state.sink.is_paused() {
                     "Paused"
+                } else if player_state
+                    .stream
+                    .as_ref()
+                    .is_some_and(|stream| stream.is_buffering())
+                {
+                    "Buffering"
                 } else {
                     "Playing"
                 };
-                frame.render_widget(
-                    Paragraph::new(vec![
-                        Line::from(vec![
-                            Span::raw("Now playing: "),
-                            Span::styled(player_state.title.as_str(), title_style),
-                        ]),
-                        Line::from(vec![
-                            Span::raw("Status: "),
-                            Span::styled(status, title_style),
-                        ]),
-                        Line::from(vec![
-                            Span::raw("Duration: "),
-                            Span::raw(format_audio_duration(player_state.sink.get_pos()).as_str()),
+                if config.compact {
+                    frame.render_widget(
+                        Paragraph::new(Line::from(vec![
+                            Span::styled(status, theme.title),
+                            Span::raw(" "),
+                            Span::raw(format!("{:.0}%", config.volume * 100.0)),
+                            Span::raw(" "),
+                            Span::raw(format!("{:.1}x", config.speed)),
+                            Span::raw(" "),
+                            Span::raw(format_audio_duration(player_state.position())),
                             Span::raw("/"),
-                            Span::raw(format_audio_duration(player_state.duration).as_str()),
+                            Span::raw(format_optional_duration(player_state.duration)),
+                            Span::raw(
+                                player_state
+                                    .loop_label()
+                                    .map(|label| format!(" Loop {label}"))
+                                    .unwrap_or_default(),
+                            ),
+                            Span::raw(" "),
+                            Span::raw(
+                                sleep_deadline
+                                    .map(|deadline| {
+                                        format!(
+                                            "Sleep {} ",
+                                            format_audio_duration(
+                                                deadline.saturating_duration_since(Instant::now())
+                                            )
+                                        )
+                                    })
+                                    .unwrap_or_default(),
+                            ),
+                            Span::styled(player_state.title.as_str(), theme.title),
+                            Span::raw(
+                                chapter_title
+                                    .map(|title| format!(" / {title}"))
+                                    .unwrap_or_default(),
+                            ),
+                        ])),
+                        compact_player_area,
+                    );
+                } else {
+                    let player_block = Block::bordered()
+                        .border_style(theme.border)
+                        .title(Span::styled("Player", theme.title));
+                    let player_layout = Layout::default()
+                        .direction(Direction::Vertical)
+                        .constraints([Constraint::Length(3), Constraint::Length(1)])
+                        .split(player_block.inner(main_layout[2]));
+                    frame.render_widget(player_block, main_layout[2]);
+
+                    frame.render_widget(
+                        Paragraph::new(vec![
+                            Line::from(vec![
+                                Span::raw("Now playing: "),
+                                Span::styled(player_state.title.as_str(), theme.title),
+                                Span::raw(
+                                    chapter_title
+                                        .map(|title| format!(" / Chapter: {title}"))
+                                        .unwrap_or_default(),
+                                ),
+                            ]),
+                            Line::from(vec![
+                                Span::raw("Status: "),
+                                Span::styled(status, theme.title),
+                                Span::raw(" Volume: "),
+                                Span::styled(format!("{:.0}%", config.volume * 100.0), theme.title),
+                                Span::raw(" Speed: "),
+                                Span::styled(format!("{:.1}x", config.speed), theme.title),
+                                Span::raw(" Continuous: "),
+                                Span::styled(
+                                    if config.auto_advance { "On" } else { "Off" },
+                                    theme.title,
+                                ),
+                                Span::raw(" Skip silence: "),
+                                Span::styled(
+                                    if config.skip_silence { "On" } else { "Off" },
+                                    theme.title,
+                                ),
+                                Span::raw(" Queue: "),
+                                Span::styled(queue.len().to_string(), theme.title),
+                                Span::raw(
+                                    player_state
+                                        .loop_label()
+                                        .map(|label| format!(" Loop: {label}"))
+                                        .unwrap_or_default(),
+                                ),
+                            ]),
+                            Line::from(vec![
+                                Span::raw("Duration: "),
+                                Span::raw(
+                                    format_audio_duration(player_state.position()).as_str(),
+                                ),
+                                Span::raw("/"),
+                                Span::raw(format_optional_duration(player_state.duration).as_str()),
+                                Span::raw(
+                                    sleep_deadline
+                                        .map(|deadline| {
+                                            format!(
+                                                " Sleep: {}",
+                                                format_audio_duration(
+                                                    deadline
+                                                        .saturating_duration_since(Instant::now())
+                                                )
+                                            )
+                                        })
+                                        .unwrap_or_default(),
+                                ),
+                            ]),
                         ]),
-                    ])
-                    .block(Block::bordered().title(Span::styled("Player", title_style))),
+                        player_layout[0],
+                    );
+
+                    if let Some(duration) = player_state.duration {
+                        let ratio =
+                            player_state.position().as_secs_f64() / duration.as_secs_f64();
+                        frame.render_widget(
+                            LineGauge::default()
+                                .ratio(ratio.clamp(0.0, 1.0))
+                                .filled_style(theme.gauge),
+                            player_layout[1],
+                        );
+                    }
+                }
+            } else if config.compact {
+                frame.render_widget(
+                    Paragraph::new(match &loading_episode {
+                        Some((_, title)) => format!("{spinner} Loading {title}…"),
+                        None => "Stopped".to_string(),
+                    }),
+                    compact_player_area,
+                );
+            } else if let Some((_, title)) = &loading_episode {
+                let player_block = Block::bordered()
+                    .border_style(theme.border)
+                    .title(Span::styled("Player", theme.title));
+                frame.render_widget(
+                    Paragraph::new(Line::from(vec![
+                        Span::raw(spinner),
+                        Span::raw(" Loading: "),
+                        Span::styled(title.as_str(), theme.title),
+                    ]))
+                    .block(player_block),
                     main_layout[2],
                 );
             } else {
                 frame.render_widget(
-                    Block::bordered().title(Span::styled("Player", title_style)),
+                    Block::bordered()
+                        .border_style(theme.border)
+                        .title(Span::styled("Player", theme.title)),
                     main_layout[2],
                 );
             }
-        })?;
-
-        if event::poll(Duration::from_millis(250))? {
-            match event::read()? {
-                Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
-                    match view_stack.last() {
-                        Some(view_kind) => match view_kind {
-                            ViewKind::PodcastInfo => match key_event.code {
-                                KeyCode::Esc => _ = view_stack.pop(),
-                                KeyCode::Char(' ') => {
-                                    if let Some(player_state) = &player {
-                                        if player_state.sink.is_paused() {
-                                            player_state.sink.play();
+        });
+        if let Err(err) = draw_result {
+            loop_error = Some(err);
+            break;
+        }
+
+        let event = match event::poll(Duration::from_millis(250))
+            .and_then(|is_ready| is_ready.then(event::read).transpose())
+        {
+            Ok(event) => event,
+            Err(err) => {
+                loop_error = Some(err);
+                break;
+            }
+        };
+        if let Some(event) = event {
+            let event_result: Result<(), AnyError> = async {
+                match event {
+                    Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                        status_message = None;
+                        let is_ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
+                        // Raw mode delivers Ctrl-C as a key press instead of a signal.
+                        if is_ctrl && key_event.code == KeyCode::Char('c') {
+                            should_quit = true;
+                            return Ok(());
+                        }
+                        let is_gg = key_event.code == KeyCode::Char('g') && pending_g;
+                        pending_g = key_event.code == KeyCode::Char('g') && !is_gg;
+                        let was_paused = player
+                            .as_ref()
+                            .is_some_and(|player_state| player_state.sink.is_paused());
+                        let mut config_changed = false;
+                        let mut player_action = None;
+                        match view_stack.last() {
+                            Some(view_kind) => match view_kind {
+                                ViewKind::PodcastInfo => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    _ if info_scroll.handle_key(key_event) => {}
+                                    KeyCode::Char(key @ ('[' | ']')) => {
+                                        let podcast = &mut podcasts[current_podcast];
+                                        load_podcast_episodes_from_path(podcast, &data_path)
+                                            .await?;
+                                        let step =
+                                            if key == ']' { GAIN_STEP_DB } else { -GAIN_STEP_DB };
+                                        podcast.gain_db =
+                                            (podcast.gain_db + step).clamp(-MAX_GAIN_DB, MAX_GAIN_DB);
+                                        save_podcast_info_to_path(podcast, &data_path).await?;
+                                        if let Some(player_state) = &mut player
+                                            && player_state.podcast_url == podcast.url
+                                        {
+                                            player_state.gain =
+                                                rodio::math::db_to_linear(podcast.gain_db);
+                                            player_state.set_volume(config.volume);
+                                        }
+                                    }
+                                    key_code => {
+                                        player_action =
+                                            handle_player_key(key_code, &player, &mut config);
+                                    }
+                                },
+                                ViewKind::ExportOpml => match key_event.code {
+                                    KeyCode::Esc => {
+                                        opml_path_input.clear();
+                                        _ = view_stack.pop();
+                                    }
+                                    KeyCode::Enter => {
+                                        let path =
+                                            expand_home_path(opml_path_input.value(), home_path.as_deref())?;
+                                        export_opml(&podcasts, &path).await?;
+                                        opml_path_input.clear();
+                                        _ = view_stack.pop();
+                                        status_message = Some(format!(
+                                            "Exported {} podcasts to {}",
+                                            podcasts.len(),
+                                            path.display()
+                                        ));
+                                    }
+                                    key_code => _ = opml_path_input.handle_key(key_code),
+                                },
+                                ViewKind::ImportSubscriptions => match key_event.code {
+                                    KeyCode::Esc => {
+                                        opml_path_input.clear();
+                                        _ = view_stack.pop();
+                                    }
+                                    KeyCode::Enter => {
+                                        let path = expand_home_path(
+                                            opml_path_input.value(),
+                                            home_path.as_deref(),
+                                        )?;
+                                        let is_opml = path.extension().is_some_and(|ext| {
+                                            ext.eq_ignore_ascii_case("opml")
+                                                || ext.eq_ignore_ascii_case("xml")
+                                        });
+                                        let urls = if is_opml {
+                                            import_opml(&path).await?
                                         } else {
-                                            player_state.sink.pause();
+                                            import_url_list(&path).await?
+                                        };
+                                        opml_path_input.clear();
+                                        _ = view_stack.pop();
+
+                                        let mut invalid_lines = Vec::new();
+                                        let urls = urls
+                                            .into_iter()
+                                            .filter_map(|line| match validate_feed_url(&line) {
+                                                Ok(url) => Some(url),
+                                                Err(err) => {
+                                                    invalid_lines.push(format!("{line}: {err}"));
+                                                    None
+                                                }
+                                            })
+                                            .collect::<Vec<_>>();
+                                        // Files often list the same feed twice, too.
+                                        let mut seen_urls = HashSet::new();
+                                        let (new_urls, duplicate_urls): (Vec<_>, Vec<_>) =
+                                            urls.into_iter().partition(|url| {
+                                                find_podcast_by_url(&podcasts, url).is_none()
+                                                    && seen_urls.insert(normalize_feed_url(url))
+                                            });
+                                        let progress = import_progress.get_or_insert_with(|| {
+                                            BatchProgress::new("Importing", 0, 0)
+                                        });
+                                        progress.total += new_urls.len() + invalid_lines.len();
+                                        progress.completed += invalid_lines.len();
+                                        progress.skipped += duplicate_urls.len();
+                                        progress.failed.extend(invalid_lines);
+                                        for url in new_urls {
+                                            worker
+                                                .send(WorkerRequest::FetchFeed {
+                                                    url,
+                                                    kind: FeedRequestKind::Import,
+                                                    validators: FeedValidators::default(),
+                                                    credentials: None,
+                                                })
+                                                .map_err(|_| "background worker stopped")?;
                                         }
                                     }
+                                    key_code => _ = opml_path_input.handle_key(key_code),
+                                },
+                                ViewKind::AddPodcast => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    KeyCode::Tab => add_podcast_field = add_podcast_field.next(),
+                                    KeyCode::BackTab => {
+                                        add_podcast_field = add_podcast_field.previous();
+                                    }
+                                    KeyCode::Char('v') if is_ctrl => {
+                                        let text =
+                                            open_clipboard(&mut clipboard)?.get_text()?.trim().to_string();
+                                        match add_podcast_field {
+                                            AddPodcastField::Url => &mut add_podcast_input,
+                                            AddPodcastField::Username => &mut add_username_input,
+                                            AddPodcastField::Password => &mut add_password_input,
+                                        }
+                                        .set_value(text);
+                                    }
+                                    KeyCode::Char('f') if is_ctrl => {
+                                        is_editing_search = true;
+                                        view_stack.push(ViewKind::SearchPodcast);
+                                    }
+                                    KeyCode::Enter => {
+                                        let url = match validate_feed_url(add_podcast_input.value())
+                                        {
+                                            Ok(url) => url,
+                                            Err(message) => {
+                                                status_message = Some(message.to_string());
+                                                return Ok(());
+                                            }
+                                        };
+                                        if let Some(podcast) = find_podcast_by_url(&podcasts, &url)
+                                        {
+                                            status_message = Some(format!(
+                                                "Already subscribed to {}",
+                                                podcast.title
+                                            ));
+                                            return Ok(());
+                                        }
+
+                                        let credentials = (!add_username_input.value().is_empty())
+                                            .then(|| FeedCredentials {
+                                                username: add_username_input.value().to_string(),
+                                                password: add_password_input.value().to_string(),
+                                            });
+                                        if let Some(credentials) = &credentials {
+                                            feed_credentials.insert(url.clone(), credentials.clone());
+                                        }
+                                        worker
+                                            .send(WorkerRequest::FetchFeed {
+                                                url,
+                                                kind: FeedRequestKind::Add,
+                                                validators: FeedValidators::default(),
+                                                credentials,
+                                            })
+                                            .map_err(|_| "background worker stopped")?;
+                                        pending_adds += 1;
+                                        add_podcast_input.clear();
+                                        add_username_input.clear();
+                                        add_password_input.clear();
+                                        add_podcast_field = AddPodcastField::Url;
+                                        _ = view_stack.pop();
+                                    }
+                                    key_code => {
+                                        _ = match add_podcast_field {
+                                            AddPodcastField::Url => &mut add_podcast_input,
+                                            AddPodcastField::Username => &mut add_username_input,
+                                            AddPodcastField::Password => &mut add_password_input,
+                                        }
+                                        .handle_key(key_code);
+                                    }
+                                },
+                                ViewKind::SearchPodcast if is_editing_search => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    KeyCode::Enter if !search_input.value().trim().is_empty() => {
+                                        let term = search_input.value().trim().to_string();
+                                        worker
+                                            .send(WorkerRequest::SearchPodcasts {
+                                                term: term.clone(),
+                                            })
+                                            .map_err(|_| "background worker stopped")?;
+                                        pending_search = Some(term);
+                                        is_editing_search = false;
+                                    }
+                                    key_code => _ = search_input.handle_key(key_code),
+                                },
+                                ViewKind::SearchPodcast => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    KeyCode::Char('/') => is_editing_search = true,
+                                    KeyCode::Char('k') => search_results_state.select(previous_selection(
+                                        search_results_state.selected(),
+                                        search_results.len(),
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('j') => search_results_state.select(next_selection(
+                                        search_results_state.selected(),
+                                        search_results.len(),
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('g') if is_gg => search_results_state.select_first(),
+                                    KeyCode::Char('G') => search_results_state.select_last(),
+                                    KeyCode::Enter => {
+                                        if let Some(result) = search_results_state
+                                            .selected()
+                                            .and_then(|selected| search_results.get(selected))
+                                        {
+                                            worker
+                                                .send(WorkerRequest::FetchFeed {
+                                                    url: result.feed_url.clone(),
+                                                    kind: FeedRequestKind::Add,
+                                                    validators: FeedValidators::default(),
+                                                    credentials: None,
+                                                })
+                                                .map_err(|_| "background worker stopped")?;
+                                            pending_adds += 1;
+                                            view_stack.truncate(
+                                                view_stack.len().saturating_sub(2),
+                                            );
+                                        }
+                                    }
+                                    _ => {}
+                                },
+                                ViewKind::ErrorInfo => {
+                                    error_info_popup_state.handle_key(key_event.code);
+                                    if error_info_popup_state.is_dismissed() {
+                                        _ = view_stack.pop();
+                                    }
                                 }
-                                _ => {}
-                            },
-                            ViewKind::AddPodcast => match key_event.code {
-                                KeyCode::Esc => _ = view_stack.pop(),
-                                KeyCode::Char('p') => {
-                                    add_podcast_url = clipboard.get_text()?;
-                                }
-                                KeyCode::Enter => {
-                                    let podcast =
-                                        download_podcast_info_from_url(&add_podcast_url).await?;
-                                    save_podcast_info_to_path(&podcast, &data_path).await?;
-
-                                    podcasts.push(podcast);
-                                    add_podcast_url.clear();
-                                    _ = view_stack.pop();
+                                ViewKind::Confirm(action) => {
+                                    let action = action.clone();
+                                    confirm_popup_state.handle_key(key_event.code);
+                                    match confirm_popup_state.decision() {
+                                        Some(true) => {
+                                            _ = view_stack.pop();
+                                            match action {
+                                                ConfirmAction::DeletePodcast(podcast_url) => {
+                                                    let Some(index) = podcasts
+                                                        .iter()
+                                                        .position(|podcast| podcast.url == podcast_url)
+                                                    else {
+                                                        return Ok(());
+                                                    };
+                                                    let podcast = podcasts.remove(index);
+                                                    if feed_credentials.remove(&podcast.url).is_some() {
+                                                        save_credentials_to_path(
+                                                            &feed_credentials,
+                                                            &data_path,
+                                                        )
+                                                        .await?;
+                                                    }
+                                                    if let Some(player_state) = &player
+                                                        && player_state.podcast_url == podcast.url
+                                                    {
+                                                        player_state.sink.clear();
+                                                        player = None;
+                                                    }
+
+                                                    let podcast_dir =
+                                                        podcast_dir(&podcast, &data_path);
+                                                    if podcast_dir.exists() {
+                                                        tokio::fs::remove_dir_all(podcast_dir)
+                                                            .await?;
+                                                        is_cache_changed = true;
+                                                    }
+                                                    save_podcast_order_to_path(
+                                                        &podcasts, &data_path,
+                                                    )
+                                                    .await?;
+
+                                                    podcast_list_state.select(clamp_selection(
+                                                        podcast_list_state.selected(),
+                                                        filter_podcasts(&podcasts, &podcast_query)
+                                                            .len(),
+                                                    ));
+                                                }
+                                                ConfirmAction::DeleteDownload(episode_url) => {
+                                                    let Some((podcast, episode)) =
+                                                        find_episode(&podcasts, &episode_url)
+                                                    else {
+                                                        return Ok(());
+                                                    };
+                                                    if let Some(player_state) = &player
+                                                        && player_state.episode_url == episode.url
+                                                    {
+                                                        status_message = Some(
+                                                            "Stop playback before deleting this download"
+                                                                .to_string(),
+                                                        );
+                                                        return Ok(());
+                                                    }
+
+                                                    remove_podcast_audio_in_path(
+                                                        podcast,
+                                                        episode,
+                                                        &data_path,
+                                                        &config.filename_template,
+                                                    )
+                                                    .await?;
+                                                    is_cache_changed = true;
+                                                    status_message =
+                                                        Some(format!("Deleted {}", episode.title));
+                                                }
+                                                ConfirmAction::MarkAllUnplayed(podcast_url) => {
+                                                    let Some(podcast) = podcasts
+                                                        .iter_mut()
+                                                        .find(|podcast| podcast.url == podcast_url)
+                                                    else {
+                                                        return Ok(());
+                                                    };
+                                                    let changed =
+                                                        mark_all_episodes(podcast, false, &mut stats);
+                                                    save_podcast_info_to_path(podcast, &data_path)
+                                                        .await?;
+                                                    status_message = Some(format!(
+                                                        "Marked {changed} episodes of {} unplayed",
+                                                        podcast.title
+                                                    ));
+                                                }
+                                            }
+                                        }
+                                        Some(false) => _ = view_stack.pop(),
+                                        None => {}
+                                    }
                                 }
-                                _ => {}
-                            },
-                            ViewKind::EpisodeList => match key_event.code {
-                                KeyCode::Esc => _ = view_stack.pop(),
-                                KeyCode::Char('i') => {
-                                    if episode_list_table_state.selected().is_some() {
+                                ViewKind::ExportEpisode(episode_url) => match key_event.code {
+                                    KeyCode::Esc => {
+                                        export_path_input.clear();
+                                        _ = view_stack.pop();
+                                    }
+                                    KeyCode::Enter => {
+                                        let Some((podcast, episode)) =
+                                            find_episode(&podcasts, episode_url)
+                                        else {
+                                            return Ok(());
+                                        };
+                                        let destination = expand_home_path(
+                                            export_path_input.value(),
+                                            home_path.as_deref(),
+                                        )?;
+                                        worker
+                                            .send(WorkerRequest::ExportEpisode {
+                                                podcast: Box::new(podcast.clone()),
+                                                episode: Box::new(episode.clone()),
+                                                destination,
+                                            })
+                                            .map_err(|_| "background worker stopped")?;
+                                        pending_exports += 1;
+                                        export_path_input.clear();
+                                        _ = view_stack.pop();
+                                    }
+                                    key_code => _ = export_path_input.handle_key(key_code),
+                                },
+                                ViewKind::GoToTime => match key_event.code {
+                                    KeyCode::Esc => {
+                                        go_to_time_input.clear();
+                                        _ = view_stack.pop();
+                                    }
+                                    KeyCode::Enter => {
+                                        let Some(position) =
+                                            parse_timestamp(go_to_time_input.value())
+                                        else {
+                                            status_message = Some(
+                                                "Enter a time as MM:SS or HH:MM:SS".to_string(),
+                                            );
+                                            return Ok(());
+                                        };
+                                        go_to_time_input.clear();
+                                        _ = view_stack.pop();
+                                        let Some(player_state) = &player else {
+                                            return Ok(());
+                                        };
+                                        let position = player_state
+                                            .duration
+                                            .map_or(position, |duration| position.min(duration));
+                                        player_state
+                                            .sink
+                                            .try_seek(position)
+                                            .map_err(|err| format!("Couldn't seek: {err}"))?;
+                                    }
+                                    key_code => _ = go_to_time_input.handle_key(key_code),
+                                },
+                                ViewKind::SleepTimer => match key_event.code {
+                                    KeyCode::Esc => {
+                                        sleep_timer_minutes.clear();
+                                        _ = view_stack.pop();
+                                    }
+                                    KeyCode::Char(c) if c.is_ascii_digit() => {
+                                        sleep_timer_minutes.push(c);
+                                    }
+                                    KeyCode::Backspace => _ = sleep_timer_minutes.pop(),
+                                    KeyCode::Enter => {
+                                        sleep_deadline = match sleep_timer_minutes.parse::<u64>() {
+                                            Ok(0) => None,
+                                            Ok(minutes) if minutes <= MAX_SLEEP_MINUTES => Some(
+                                                Instant::now() + Duration::from_secs(minutes * 60),
+                                            ),
+                                            _ => {
+                                                status_message = Some(format!(
+                                                    "Enter 0 to {MAX_SLEEP_MINUTES} minutes"
+                                                ));
+                                                return Ok(());
+                                            }
+                                        };
+                                        sleep_timer_minutes.clear();
+                                        _ = view_stack.pop();
+                                    }
+                                    _ => {}
+                                },
+                                ViewKind::EpisodeList => match key_event.code {
+                                    key_code if is_searching => {
+                                        let podcast = &podcasts[current_podcast];
+                                        let selected_index = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        );
+                                        is_searching =
+                                            edit_search_query(&mut episode_filter.query, key_code);
+                                        select_episode_index(
+                                            &mut episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                            selected_index,
+                                        );
+                                    }
+                                    KeyCode::Esc if !episode_filter.query.is_empty() => {
+                                        let podcast = &podcasts[current_podcast];
+                                        let selected_index = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        );
+                                        episode_filter.query.clear();
+                                        select_episode_index(
+                                            &mut episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                            selected_index,
+                                        );
+                                    }
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    KeyCode::Char('/') => is_searching = true,
+                                    KeyCode::Char('s') => view_stack.push(ViewKind::SleepTimer),
+                                    KeyCode::Char('y') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        if let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        ) {
+                                            let episode = &podcast.episodes[episode_index];
+                                            open_clipboard(&mut clipboard)?
+                                                .set_text(episode.url.as_str())?;
+                                            status_message = Some(format!(
+                                                "Copied audio url of {}",
+                                                episode.title
+                                            ));
+                                        }
+                                    }
+                                    KeyCode::Char('b') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        if let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        ) {
+                                            let episode = &podcast.episodes[episode_index];
+                                            open_in_browser(
+                                                episode.link.as_deref().unwrap_or(&episode.url),
+                                            )?;
+                                        }
+                                    }
+                                    KeyCode::Char('Y') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        open_clipboard(&mut clipboard)?
+                                            .set_text(podcast.url.as_str())?;
+                                        status_message =
+                                            Some(format!("Copied feed url of {}", podcast.title));
+                                    }
+                                    KeyCode::Char('e') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        if let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        ) {
+                                            status_message = Some(enqueue_episode(
+                                                &mut queue,
+                                                podcast,
+                                                &podcast.episodes[episode_index],
+                                            ));
+                                        }
+                                    }
+                                    KeyCode::Char('Q') => {
+                                        queue_list_state.select(None);
+                                        view_stack.push(ViewKind::Queue);
+                                    }
+                                    KeyCode::Char('x') => {
+                                        let podcast_index = current_podcast;
+                                        if let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(
+                                                &podcasts[podcast_index],
+                                                &episode_filter,
+                                            ),
+                                        ) {
+                                            let podcast = &mut podcasts[podcast_index];
+                                            if podcast.episodes[episode_index].played {
+                                                podcast.episodes[episode_index].played = false;
+                                            } else {
+                                                mark_played(podcast, episode_index, &mut stats);
+                                                save_stats_to_path(&stats, &data_path).await?;
+                                            }
+                                            save_podcast_info_to_path(podcast, &data_path).await?;
+                                        }
+                                    }
+                                    KeyCode::Char('f') => {
+                                        let podcast_index = current_podcast;
+                                        if let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(
+                                                &podcasts[podcast_index],
+                                                &episode_filter,
+                                            ),
+                                        ) {
+                                            let podcast = &mut podcasts[podcast_index];
+                                            let episode = &mut podcast.episodes[episode_index];
+                                            episode.favorite = !episode.favorite;
+                                            save_podcast_info_to_path(podcast, &data_path).await?;
+                                        }
+                                    }
+                                    KeyCode::Char('i')
+                                        if episode_list_table_state.selected().is_some() =>
+                                    {
+                                        let podcast = &podcasts[current_podcast];
+                                        if config.fetch_episode_size
+                                            && let Some(episode_index) = selected_episode_index(
+                                                &episode_list_table_state,
+                                                &filter_episodes(podcast, &episode_filter),
+                                            )
+                                        {
+                                            let episode = &podcast.episodes[episode_index];
+                                            if !episode_sizes.contains_key(&episode.url)
+                                                && pending_episode_sizes.insert(episode.url.clone())
+                                            {
+                                                worker
+                                                    .send(WorkerRequest::FetchEpisodeSize {
+                                                        episode: Box::new(episode.clone()),
+                                                    })
+                                                    .map_err(|_| "background worker stopped")?;
+                                            }
+                                        }
+                                        info_scroll = InfoScroll::default();
                                         view_stack.push(ViewKind::EpisodeInfo);
                                     }
-                                }
-                                KeyCode::Char('k') => episode_list_table_state.select_previous(),
-                                KeyCode::Char('j') => episode_list_table_state.select_next(),
-                                KeyCode::Enter => {
-                                    if episode_list_table_state.selected().is_some() {
-                                        if let Some(player_state) = &player {
-                                            player_state.sink.clear();
-                                        }
-
-                                        let podcast =
-                                            &podcasts[podcast_list_state.selected().unwrap()];
-                                        let episode = &podcast.episodes
-                                            [episode_list_table_state.selected().unwrap()];
-                                        let audio_file = download_podcast_audio_to_path(
-                                            podcast, episode, &data_path,
+                                    KeyCode::Char('k') => {
+                                        episode_list_table_state.select(previous_selection(
+                                            episode_list_table_state.selected(),
+                                            filter_episodes(
+                                                &podcasts[current_podcast],
+                                                &episode_filter,
+                                            )
+                                            .len(),
+                                            config.wrap_navigation,
+                                        ))
+                                    }
+                                    KeyCode::Char('j') => {
+                                        episode_list_table_state.select(next_selection(
+                                            episode_list_table_state.selected(),
+                                            filter_episodes(
+                                                &podcasts[current_podcast],
+                                                &episode_filter,
+                                            )
+                                            .len(),
+                                            config.wrap_navigation,
+                                        ))
+                                    }
+                                    KeyCode::Char('g') if is_gg => episode_list_table_state.select_first(),
+                                    KeyCode::Char('G') => episode_list_table_state.select_last(),
+                                    KeyCode::Char('t') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        let selected_index = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        );
+
+                                        episode_filter.full_only = !episode_filter.full_only;
+                                        select_episode_index(
+                                            &mut episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                            selected_index,
+                                        );
+                                    }
+                                    KeyCode::Char('E') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        let selected_index = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        );
+
+                                        episode_filter.hide_explicit = !episode_filter.hide_explicit;
+                                        select_episode_index(
+                                            &mut episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                            selected_index,
+                                        );
+                                    }
+                                    KeyCode::Char('O') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        let selected_index = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        );
+
+                                        episode_filter.downloads = episode_filter.downloads.next();
+                                        if episode_filter.downloads != DownloadFilter::All {
+                                            episode_filter.downloaded = downloaded_episode_urls(
+                                                &podcasts,
+                                                &data_path,
+                                                &config.filename_template,
+                                            );
+                                        }
+                                        select_episode_index(
+                                            &mut episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                            selected_index,
+                                        );
+                                    }
+                                    KeyCode::Char('o') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        let selected_index = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        );
+
+                                        episode_filter.sort = episode_filter.sort.next();
+                                        select_episode_index(
+                                            &mut episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                            selected_index,
+                                        );
+                                    }
+                                    KeyCode::Char('X') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        if let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        ) && check_podcast_audio_in_path(
+                                            podcast,
+                                            &podcast.episodes[episode_index],
+                                            &data_path,
+                                            &config.filename_template,
+                                        ) {
+                                            let episode = &podcast.episodes[episode_index];
+                                            confirm_popup_state = ConfirmPopupState::new(
+                                                "Delete download",
+                                                if episode.favorite {
+                                                    format!(
+                                                        "{} is a favorite. Delete its downloaded audio anyway?",
+                                                        episode.title
+                                                    )
+                                                } else {
+                                                    format!(
+                                                        "Delete the downloaded audio for {}?",
+                                                        episode.title
+                                                    )
+                                                },
+                                            );
+                                            view_stack.push(ViewKind::Confirm(
+                                                ConfirmAction::DeleteDownload(episode.url.clone()),
+                                            ));
+                                        }
+                                    }
+                                    KeyCode::Char('T') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        ) else {
+                                            return Ok(());
+                                        };
+                                        let episode = &podcast.episodes[episode_index];
+                                        if episode.transcript.is_none() {
+                                            status_message = Some(format!(
+                                                "{} has no transcript",
+                                                episode.title
+                                            ));
+                                            return Ok(());
+                                        }
+                                        worker
+                                            .send(WorkerRequest::FetchTranscript {
+                                                podcast: Box::new(podcast.clone()),
+                                                episode: Box::new(episode.clone()),
+                                            })
+                                            .map_err(|_| "background worker stopped")?;
+                                        pending_transcript = Some(episode.url.clone());
+                                    }
+                                    KeyCode::Char('w') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        if let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        ) {
+                                            export_path_input.clear();
+                                            view_stack.push(ViewKind::ExportEpisode(
+                                                podcast.episodes[episode_index].url.clone(),
+                                            ));
+                                        }
+                                    }
+                                    KeyCode::Char('D') => {
+                                        let podcast = &podcasts[current_podcast];
+                                        if let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        ) {
+                                            worker
+                                                .send(WorkerRequest::DownloadEpisode {
+                                                    podcast: Box::new(podcast.clone()),
+                                                    episode: Box::new(
+                                                        podcast.episodes[episode_index].clone(),
+                                                    ),
+                                                })
+                                                .map_err(|_| "background worker stopped")?;
+                                            pending_downloads += 1;
+                                        }
+                                    }
+                                    KeyCode::Char('A') => {
+                                        if let Some(batch) = &mut download_batch {
+                                            let cancelled = batch.cancel();
+                                            status_message =
+                                                Some(format!("Cancelled {cancelled} downloads"));
+                                            return Ok(());
+                                        }
+
+                                        let podcast = &podcasts[current_podcast];
+                                        let (downloaded, new_episodes): (Vec<_>, Vec<_>) = podcast
+                                            .episodes
+                                            .iter()
+                                            .filter(|episode| !episode.played)
+                                            .partition(|episode| {
+                                                check_podcast_audio_in_path(
+                                                    podcast,
+                                                    episode,
+                                                    &data_path,
+                                                    &config.filename_template,
+                                                )
+                                            });
+                                        if new_episodes.is_empty() {
+                                            status_message = Some(format!(
+                                                "No new episodes to download for {}",
+                                                podcast.title
+                                            ));
+                                            return Ok(());
+                                        }
+
+                                        let mut batch = DownloadBatch {
+                                            progress: BatchProgress::new(
+                                                "Downloading",
+                                                new_episodes.len(),
+                                                downloaded.len(),
+                                            ),
+                                            pending: new_episodes
+                                                .into_iter()
+                                                .map(|episode| {
+                                                    (podcast.clone(), episode.clone())
+                                                })
+                                                .collect(),
+                                            in_flight: HashSet::new(),
+                                        };
+                                        batch.send_next(&worker, config.max_concurrent_downloads)?;
+                                        download_batch = Some(batch);
+                                    }
+                                    KeyCode::Enter
+                                        if episode_list_table_state.selected().is_some() =>
+                                    {
+                                        if let Some(player_state) = player.take() {
+                                            save_playback_position(
+                                                &player_state,
+                                                &mut podcasts,
+                                                &mut stats,
+                                                &data_path,
+                                            )
+                                            .await?;
+                                        }
+
+                                        let podcast = &podcasts[current_podcast];
+                                        let Some(episode_index) = selected_episode_index(
+                                            &episode_list_table_state,
+                                            &filter_episodes(podcast, &episode_filter),
+                                        ) else {
+                                            return Ok(());
+                                        };
+                                        let episode = &podcast.episodes[episode_index];
+                                        loading_episode = Some(request_episode(
+                                            &worker, podcast, episode, &config,
+                                        )?);
+                                    }
+                                    key_code => {
+                                        player_action =
+                                            handle_player_key(key_code, &player, &mut config);
+                                    }
+                                },
+                                ViewKind::EpisodeInfo => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    _ if info_scroll.handle_key(key_event) => {}
+                                    key_code => {
+                                        player_action =
+                                            handle_player_key(key_code, &player, &mut config);
+                                    }
+                                },
+                                ViewKind::Stats | ViewKind::Failures => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    _ if info_scroll.handle_key(key_event) => {}
+                                    key_code => {
+                                        player_action =
+                                            handle_player_key(key_code, &player, &mut config);
+                                    }
+                                },
+                                ViewKind::Transcript => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    _ if info_scroll.handle_key(key_event) => {}
+                                    KeyCode::Char('t') => {
+                                        if let Some(transcript) = &mut transcript_view {
+                                            transcript.with_timestamps = !transcript.with_timestamps;
+                                        }
+                                    }
+                                    key_code => {
+                                        player_action =
+                                            handle_player_key(key_code, &player, &mut config);
+                                    }
+                                },
+                                ViewKind::Chapters => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    KeyCode::Char('k') => chapter_list_state.select_previous(),
+                                    KeyCode::Char('j') => chapter_list_state.select_next(),
+                                    KeyCode::Char('g') if is_gg => chapter_list_state.select_first(),
+                                    KeyCode::Char('G') => chapter_list_state.select_last(),
+                                    KeyCode::Enter => {
+                                        let Some(player_state) = &player else {
+                                            return Ok(());
+                                        };
+                                        if let Some((_, episode)) =
+                                            find_episode(&podcasts, &player_state.episode_url)
+                                            && let Some(chapter) = chapter_list_state
+                                                .selected()
+                                                .and_then(|selected| episode.chapters.get(selected))
+                                        {
+                                            player_state
+                                                .sink
+                                                .try_seek(chapter.start)
+                                                .map_err(|err| format!("Couldn't seek: {err}"))?;
+                                            _ = view_stack.pop();
+                                        }
+                                    }
+                                    key_code => {
+                                        player_action =
+                                            handle_player_key(key_code, &player, &mut config);
+                                    }
+                                },
+                                ViewKind::OutputDevice => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    KeyCode::Char('k') => output_device_state.select(previous_selection(
+                                        output_device_state.selected(),
+                                        output_devices.len() + 1,
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('j') => output_device_state.select(next_selection(
+                                        output_device_state.selected(),
+                                        output_devices.len() + 1,
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('g') if is_gg => output_device_state.select_first(),
+                                    KeyCode::Char('G') => output_device_state.select_last(),
+                                    KeyCode::Enter => {
+                                        let Some(selected) = output_device_state.selected() else {
+                                            return Ok(());
+                                        };
+                                        let device_name = selected
+                                            .checked_sub(1)
+                                            .and_then(|index| output_devices.get(index))
+                                            .cloned();
+                                        _ = view_stack.pop();
+
+                                        // The sink belongs to the old stream's mixer, so the
+                                        // current episode is reloaded on the new device.
+                                        let (new_stream, _) =
+                                            open_output_stream(device_name.as_deref())?;
+                                        let resumed_episode = match player.take() {
+                                            Some(player_state) => {
+                                                let is_playing = !player_state.sink.is_paused();
+                                                save_playback_position(
+                                                    &player_state,
+                                                    &mut podcasts,
+                                                    &mut stats,
+                                                    &data_path,
+                                                )
+                                                .await?;
+                                                is_playing.then_some(player_state.episode_url)
+                                            }
+                                            None => None,
+                                        };
+                                        stream_handle = new_stream;
+                                        if let Some((podcast, episode)) = resumed_episode
+                                            .and_then(|url| find_episode(&podcasts, &url))
+                                        {
+                                            loading_episode = Some(request_episode(
+                                                &worker, podcast, episode, &config,
+                                            )?);
+                                        }
+
+                                        status_message = Some(format!(
+                                            "Playing through {}",
+                                            device_name.as_deref().unwrap_or("the system default")
+                                        ));
+                                        config.output_device = device_name;
+                                        config_changed = true;
+                                    }
+                                    _ => {}
+                                },
+                                ViewKind::Queue => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    KeyCode::Char('k') => queue_list_state.select(previous_selection(
+                                        queue_list_state.selected(),
+                                        queue.len(),
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('j') => queue_list_state.select(next_selection(
+                                        queue_list_state.selected(),
+                                        queue.len(),
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('g') if is_gg => queue_list_state.select_first(),
+                                    KeyCode::Char('G') => queue_list_state.select_last(),
+                                    KeyCode::Char('K') => {
+                                        if let Some(index) = queue_list_state.selected()
+                                            && index > 0
+                                            && index < queue.len()
+                                        {
+                                            queue.swap(index, index - 1);
+                                            queue_list_state.select(Some(index - 1));
+                                        }
+                                    }
+                                    KeyCode::Char('J') => {
+                                        if let Some(index) = queue_list_state.selected()
+                                            && index + 1 < queue.len()
+                                        {
+                                            queue.swap(index, index + 1);
+                                            queue_list_state.select(Some(index + 1));
+                                        }
+                                    }
+                                    KeyCode::Char('d') | KeyCode::Delete => {
+                                        if let Some(index) = queue_list_state.selected()
+                                            && queue.remove(index).is_some()
+                                        {
+                                            queue_list_state
+                                                .select(clamp_selection(Some(index), queue.len()));
+                                        }
+                                    }
+                                    KeyCode::Enter => {
+                                        let Some(index) = queue_list_state.selected() else {
+                                            return Ok(());
+                                        };
+                                        // Move the entry to the front so the shared lookup
+                                        // below also drops it from the queue.
+                                        let Some(queued) = queue.remove(index) else {
+                                            return Ok(());
+                                        };
+                                        queue.push_front(queued);
+                                        let Some((podcast_index, episode_index)) =
+                                            pop_queued_episode(&mut queue, &podcasts)
+                                        else {
+                                            return Ok(());
+                                        };
+
+                                        if let Some(player_state) = player.take() {
+                                            save_playback_position(
+                                                &player_state,
+                                                &mut podcasts,
+                                                &mut stats,
+                                                &data_path,
+                                            )
+                                            .await?;
+                                        }
+                                        let podcast = &podcasts[podcast_index];
+                                        loading_episode = Some(request_episode(
+                                            &worker,
+                                            podcast,
+                                            &podcast.episodes[episode_index],
+                                            &config,
+                                        )?);
+                                    }
+                                    key_code => {
+                                        player_action =
+                                            handle_player_key(key_code, &player, &mut config);
+                                    }
+                                },
+                                ViewKind::Inbox => match key_event.code {
+                                    KeyCode::Esc => _ = view_stack.pop(),
+                                    KeyCode::Char('e') => {
+                                        let entries = inbox_episodes(&podcasts, is_inbox_favorites);
+                                        if let Some(&(podcast_index, episode_index)) =
+                                            inbox_table_state
+                                                .selected()
+                                                .and_then(|selected| entries.get(selected))
+                                        {
+                                            let podcast = &podcasts[podcast_index];
+                                            status_message = Some(enqueue_episode(
+                                                &mut queue,
+                                                podcast,
+                                                &podcast.episodes[episode_index],
+                                            ));
+                                        }
+                                    }
+                                    KeyCode::Char('Q') => {
+                                        queue_list_state.select(None);
+                                        view_stack.push(ViewKind::Queue);
+                                    }
+                                    KeyCode::Char('k') => inbox_table_state.select(previous_selection(
+                                        inbox_table_state.selected(),
+                                        inbox_episodes(&podcasts, is_inbox_favorites).len(),
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('j') => inbox_table_state.select(next_selection(
+                                        inbox_table_state.selected(),
+                                        inbox_episodes(&podcasts, is_inbox_favorites).len(),
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('g') if is_gg => inbox_table_state.select_first(),
+                                    KeyCode::Char('G') => inbox_table_state.select_last(),
+                                    KeyCode::Char('F') => {
+                                        is_inbox_favorites = !is_inbox_favorites;
+                                        inbox_table_state.select(None);
+                                    }
+                                    KeyCode::Char('f') => {
+                                        let entries = inbox_episodes(&podcasts, is_inbox_favorites);
+                                        if let Some(&(podcast_index, episode_index)) =
+                                            inbox_table_state
+                                                .selected()
+                                                .and_then(|selected| entries.get(selected))
+                                        {
+                                            let podcast = &mut podcasts[podcast_index];
+                                            let episode = &mut podcast.episodes[episode_index];
+                                            episode.favorite = !episode.favorite;
+                                            save_podcast_info_to_path(podcast, &data_path).await?;
+                                        }
+                                    }
+                                    KeyCode::Char('x') => {
+                                        let entries = inbox_episodes(&podcasts, is_inbox_favorites);
+                                        if let Some(&(podcast_index, episode_index)) =
+                                            inbox_table_state
+                                                .selected()
+                                                .and_then(|selected| entries.get(selected))
+                                        {
+                                            let podcast = &mut podcasts[podcast_index];
+                                            mark_played(podcast, episode_index, &mut stats);
+                                            save_podcast_info_to_path(podcast, &data_path).await?;
+                                            save_stats_to_path(&stats, &data_path).await?;
+                                        }
+                                    }
+                                    KeyCode::Enter => {
+                                        let entries = inbox_episodes(&podcasts, is_inbox_favorites);
+                                        if let Some(&(podcast_index, episode_index)) =
+                                            inbox_table_state
+                                                .selected()
+                                                .and_then(|selected| entries.get(selected))
+                                        {
+                                            if let Some(player_state) = player.take() {
+                                                save_playback_position(
+                                                    &player_state,
+                                                    &mut podcasts,
+                                                    &mut stats,
+                                                    &data_path,
+                                                )
+                                                .await?;
+                                            }
+
+                                            let podcast = &podcasts[podcast_index];
+                                            let episode = &podcast.episodes[episode_index];
+                                            loading_episode = Some(request_episode(
+                                                &worker, podcast, episode, &config,
+                                            )?);
+                                        }
+                                    }
+                                    key_code => {
+                                        player_action =
+                                            handle_player_key(key_code, &player, &mut config);
+                                    }
+                                },
+                            },
+                            None => {
+                                let podcast_indices = filter_podcasts(&podcasts, &podcast_query);
+                                let selected_podcast = podcast_list_state
+                                    .selected()
+                                    .and_then(|selected| podcast_indices.get(selected).copied());
+
+                                match key_event.code {
+                                    key_code if is_searching => {
+                                        is_searching =
+                                            edit_search_query(&mut podcast_query, key_code);
+                                        podcast_list_state.select(remap_selection(
+                                            &filter_podcasts(&podcasts, &podcast_query),
+                                            selected_podcast,
+                                        ));
+                                    }
+                                    KeyCode::Esc if !podcast_query.is_empty() => {
+                                        podcast_query.clear();
+                                        podcast_list_state.select(remap_selection(
+                                            &filter_podcasts(&podcasts, &podcast_query),
+                                            selected_podcast,
+                                        ));
+                                    }
+                                    KeyCode::Char('/') => is_searching = true,
+                                    KeyCode::Char('q') => should_quit = true,
+                                    KeyCode::Char('u') if update_progress.is_none() => {
+                                        request_feed_updates(
+                                            &worker,
+                                            &podcasts,
+                                            &feed_credentials,
+                                            FeedRequestKind::Update,
+                                        )?;
+                                        update_progress =
+                                            Some(BatchProgress::new("Updating", podcasts.len(), 0));
+                                    }
+                                    KeyCode::Char('a') => view_stack.push(ViewKind::AddPodcast),
+                                    KeyCode::Char('I') => view_stack.push(ViewKind::ImportSubscriptions),
+                                    KeyCode::Char('E') => view_stack.push(ViewKind::ExportOpml),
+                                    KeyCode::Char('s') => view_stack.push(ViewKind::SleepTimer),
+                                    KeyCode::Char('n') | KeyCode::Char('F') => {
+                                        load_all_episodes(&mut podcasts, &data_path).await?;
+                                        is_inbox_favorites = key_event.code == KeyCode::Char('F');
+                                        inbox_table_state.select(None);
+                                        view_stack.push(ViewKind::Inbox);
+                                    }
+                                    KeyCode::Char('Q') => {
+                                        queue_list_state.select(None);
+                                        view_stack.push(ViewKind::Queue);
+                                    }
+                                    KeyCode::Char('y') => {
+                                        if let Some(index) = selected_podcast {
+                                            let podcast = &podcasts[index];
+                                            open_clipboard(&mut clipboard)?
+                                                .set_text(podcast.url.as_str())?;
+                                            status_message = Some(format!(
+                                                "Copied feed url of {}",
+                                                podcast.title
+                                            ));
+                                        }
+                                    }
+                                    KeyCode::Char('b') => {
+                                        if let Some(index) = selected_podcast {
+                                            let podcast = &podcasts[index];
+                                            open_in_browser(
+                                                podcast.link.as_deref().unwrap_or(&podcast.url),
+                                            )?;
+                                        }
+                                    }
+                                    KeyCode::Char('O') => {
+                                        output_devices = output_device_names();
+                                        output_device_state.select(Some(
+                                            config
+                                                .output_device
+                                                .as_ref()
+                                                .and_then(|name| {
+                                                    output_devices
+                                                        .iter()
+                                                        .position(|device| device == name)
+                                                })
+                                                .map_or(0, |index| index + 1),
+                                        ));
+                                        view_stack.push(ViewKind::OutputDevice);
+                                    }
+                                    KeyCode::Char('m') => {
+                                        config.compact = !config.compact;
+                                        config_changed = true;
+                                    }
+                                    KeyCode::Char('T') => {
+                                        config.theme = Theme::next_name(&config.theme).to_string();
+                                        theme = Theme::from_name(&config.theme);
+                                        config_changed = true;
+                                        status_message = Some(format!("Theme: {}", config.theme));
+                                    }
+                                    KeyCode::Char('k') => podcast_list_state.select(previous_selection(
+                                        podcast_list_state.selected(),
+                                        podcast_indices.len(),
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('j') => podcast_list_state.select(next_selection(
+                                        podcast_list_state.selected(),
+                                        podcast_indices.len(),
+                                        config.wrap_navigation,
+                                    )),
+                                    KeyCode::Char('g') if is_gg => podcast_list_state.select_first(),
+                                    KeyCode::Char('G') => podcast_list_state.select_last(),
+                                    KeyCode::Char('K') => {
+                                        if let Some(index) = podcast_list_state.selected()
+                                            && podcast_query.is_empty()
+                                            && index > 0
+                                        {
+                                            podcasts.swap(index, index - 1);
+                                            podcast_list_state.select(Some(index - 1));
+                                            save_podcast_order_to_path(&podcasts, &data_path)
+                                                .await?;
+                                        }
+                                    }
+                                    KeyCode::Char('J') => {
+                                        if let Some(index) = podcast_list_state.selected()
+                                            && podcast_query.is_empty()
+                                            && index + 1 < podcasts.len()
+                                        {
+                                            podcasts.swap(index, index + 1);
+                                            podcast_list_state.select(Some(index + 1));
+                                            save_podcast_order_to_path(&podcasts, &data_path)
+                                                .await?;
+                                        }
+                                    }
+                                    KeyCode::Char('i') => {
+                                        if let Some(index) = selected_podcast {
+                                            current_podcast = index;
+                                            info_scroll = InfoScroll::default();
+                                            podcast_artwork = load_podcast_artwork_from_path(
+                                                &podcasts[index],
+                                                &data_path,
+                                            );
+                                            // Walking the directory also counts
+                                            // podcasts whose episodes aren't loaded.
+                                            podcast_audio_size = audio_cache_size_in_path(
+                                                &podcast_dir(&podcasts[index], &data_path),
+                                            );
+                                            view_stack.push(ViewKind::PodcastInfo);
+                                        }
+                                    }
+                                    KeyCode::Char('P') => {
+                                        info_scroll = InfoScroll::default();
+                                        view_stack.push(ViewKind::Stats);
+                                    }
+                                    KeyCode::Char('x') => {
+                                        let Some(index) = selected_podcast else {
+                                            return Ok(());
+                                        };
+                                        let podcast = &mut podcasts[index];
+                                        load_podcast_episodes_from_path(podcast, &data_path)
+                                            .await?;
+                                        let changed = mark_all_episodes(podcast, true, &mut stats);
+                                        save_podcast_info_to_path(podcast, &data_path).await?;
+                                        save_stats_to_path(&stats, &data_path).await?;
+                                        status_message = Some(format!(
+                                            "Marked {changed} episodes of {} played",
+                                            podcast.title
+                                        ));
+                                    }
+                                    KeyCode::Char('X') => {
+                                        let Some(index) = selected_podcast else {
+                                            return Ok(());
+                                        };
+                                        let podcast = &mut podcasts[index];
+                                        load_podcast_episodes_from_path(podcast, &data_path)
+                                            .await?;
+                                        let played = podcast
+                                            .episodes
+                                            .iter()
+                                            .filter(|episode| episode.played)
+                                            .count();
+                                        confirm_popup_state = ConfirmPopupState::new(
+                                            "Mark all unplayed",
+                                            format!(
+                                                "Mark all {played} played episodes of {} as unplayed?",
+                                                podcast.title
+                                            ),
+                                        );
+                                        view_stack.push(ViewKind::Confirm(
+                                            ConfirmAction::MarkAllUnplayed(podcast.url.clone()),
+                                        ));
+                                    }
+                                    KeyCode::Char('d') => {
+                                        let Some(index) = selected_podcast else {
+                                            return Ok(());
+                                        };
+                                        current_podcast = index;
+                                        load_podcast_episodes_from_path(
+                                            &mut podcasts[current_podcast],
+                                            &data_path,
                                         )
                                         .await?;
-                                        let reader = BufReader::new(File::open(audio_file)?);
-                                        let source = rodio::Decoder::try_from(reader)?;
-
-                                        let title =
-                                            format!("{} / {}", &podcast.title, &episode.title);
-                                        let sink = Sink::connect_new(&stream_handle.mixer());
-                                        let duration = source.total_duration().unwrap_or_default();
-                                        sink.append(source);
-                                        player = Some(PlayerState {
-                                            title,
-                                            sink,
-                                            duration,
-                                        });
+                                        let podcast = &podcasts[current_podcast];
+                                        let favorites = podcast
+                                            .episodes
+                                            .iter()
+                                            .filter(|episode| episode.favorite)
+                                            .count();
+                                        confirm_popup_state = ConfirmPopupState::new(
+                                            "Delete podcast",
+                                            if favorites > 0 {
+                                                format!(
+                                                    "{} has {favorites} favorite episodes. Delete it and all of its downloaded episodes anyway?",
+                                                    podcast.title
+                                                )
+                                            } else {
+                                                format!(
+                                                    "Delete {} and all of its downloaded episodes?",
+                                                    podcast.title
+                                                )
+                                            },
+                                        );
+                                        view_stack
+                                            .push(ViewKind::Confirm(ConfirmAction::DeletePodcast(
+                                                podcast.url.clone(),
+                                            )));
                                     }
-                                }
-                                KeyCode::Char(' ') => {
-                                    if let Some(player_state) = &player {
-                                        if player_state.sink.is_paused() {
-                                            player_state.sink.play();
-                                        } else {
-                                            player_state.sink.pause();
+                                    KeyCode::Enter => {
+                                        if let Some(index) = selected_podcast {
+                                            load_podcast_episodes_from_path(
+                                                &mut podcasts[index],
+                                                &data_path,
+                                            )
+                                            .await?;
+                                            current_podcast = index;
+                                            episode_filter.query.clear();
+                                            episode_list_table_state.select(None);
+                                            view_stack.push(ViewKind::EpisodeList);
                                         }
                                     }
+                                    key_code => {
+                                        player_action =
+                                            handle_player_key(key_code, &player, &mut config);
+                                    }
                                 }
-                                _ => {}
-                            },
-                            ViewKind::EpisodeInfo => match key_event.code {
-                                KeyCode::Esc => _ = view_stack.pop(),
-                                KeyCode::Char(' ') => {
-                                    if let Some(player_state) = &player {
-                                        if player_state.sink.is_paused() {
-                                            player_state.sink.play();
-                                        } else {
-                                            player_state.sink.pause();
+                            }
+                        }
+
+                        match player_action {
+                            Some(PlayerAction::ConfigChanged) => config_changed = true,
+                            Some(PlayerAction::ShowChapters) => {
+                                if let Some(player_state) = &player
+                                    && let Some((_, episode)) =
+                                        find_episode(&podcasts, &player_state.episode_url)
+                                {
+                                    if episode.chapters.is_empty() {
+                                        status_message = Some(
+                                            if episode.chapters_url.is_some() {
+                                                "Chapters are still loading"
+                                            } else {
+                                                "This episode has no chapters"
+                                            }
+                                            .to_string(),
+                                        );
+                                    } else if !matches!(view_stack.last(), Some(ViewKind::Chapters))
+                                    {
+                                        let position = player_state.position();
+                                        chapter_list_state.select(Some(
+                                            episode
+                                                .chapters
+                                                .iter()
+                                                .rposition(|chapter| chapter.start <= position)
+                                                .unwrap_or_default(),
+                                        ));
+                                        view_stack.push(ViewKind::Chapters);
+                                    }
+                                }
+                            }
+                            Some(PlayerAction::GoToTime) => {
+                                go_to_time_input.clear();
+                                view_stack.push(ViewKind::GoToTime);
+                            }
+                            Some(PlayerAction::SetLoopStart) => {
+                                if let Some(player_state) = &mut player {
+                                    let position = player_state.position();
+                                    player_state.loop_start = Some(position);
+                                    if player_state.loop_end.is_some_and(|end| end <= position) {
+                                        player_state.loop_end = None;
+                                    }
+                                }
+                            }
+                            Some(PlayerAction::SetLoopEnd) => {
+                                if let Some(player_state) = &mut player {
+                                    let position = player_state.position();
+                                    match player_state.loop_start {
+                                        Some(start) if start < position => {
+                                            player_state.loop_end = Some(position);
+                                            _ = player_state.sink.try_seek(start);
+                                        }
+                                        Some(_) => {
+                                            status_message = Some(
+                                                "The loop must end after it starts".to_string(),
+                                            )
+                                        }
+                                        None => {
+                                            status_message = Some(
+                                                "Press ( to set where the loop starts first"
+                                                    .to_string(),
+                                            )
                                         }
                                     }
                                 }
-                                _ => {}
-                            },
-                        },
-                        None => match key_event.code {
-                            KeyCode::Char('q') => should_quit = true,
-                            KeyCode::Char('u') => {
-                                podcasts = update_all_podcast_info(
-                                    &podcasts
-                                        .iter()
-                                        .map(|podcast| podcast.url.as_str())
-                                        .collect(),
-                                    &data_path,
-                                )
-                                .await?;
                             }
-                            KeyCode::Char('a') => view_stack.push(ViewKind::AddPodcast),
-                            KeyCode::Char('k') => podcast_list_state.select_previous(),
-                            KeyCode::Char('j') => podcast_list_state.select_next(),
-                            KeyCode::Char('i') => {
-                                if podcast_list_state.selected().is_some() {
-                                    view_stack.push(ViewKind::PodcastInfo);
+                            Some(PlayerAction::ClearLoop) => {
+                                if let Some(player_state) = &mut player {
+                                    player_state.loop_start = None;
+                                    player_state.loop_end = None;
                                 }
                             }
-                            KeyCode::Enter => {
-                                if podcast_list_state.selected().is_some() {
-                                    view_stack.push(ViewKind::EpisodeList);
+                            Some(PlayerAction::Stop) => {
+                                // Dropping the loading episode too keeps a pending
+                                // auto-advance from starting right after the stop.
+                                loading_episode = None;
+                                if let Some(player_state) = player.take() {
+                                    save_playback_position(
+                                        &player_state,
+                                        &mut podcasts,
+                                        &mut stats,
+                                        &data_path,
+                                    )
+                                    .await?;
+                                    player_state.sink.stop();
                                 }
                             }
-                            KeyCode::Char(' ') => {
-                                if let Some(player_state) = &player {
-                                    if player_state.sink.is_paused() {
-                                        player_state.sink.play();
-                                    } else {
-                                        player_state.sink.pause();
-                                    }
+                            None => {}
+                        }
+                        if config_changed {
+                            save_config_to_path(&config, &data_path).await?;
+                        }
+
+                        if let Some(player_state) = &player
+                            && player_state.sink.is_paused()
+                            && !was_paused
+                        {
+                            save_playback_position(
+                                player_state,
+                                &mut podcasts,
+                                &mut stats,
+                                &data_path,
+                            )
+                            .await?;
+                        }
+                    }
+                    Event::Mouse(mouse_event) => {
+                        let position = Position::new(mouse_event.column, mouse_event.row);
+                        let click = (mouse_event.kind == MouseEventKind::Down(MouseButton::Left))
+                            .then_some(position);
+                        let scroll = match mouse_event.kind {
+                            MouseEventKind::ScrollDown => Some(true),
+                            MouseEventKind::ScrollUp => Some(false),
+                            _ => None,
+                        };
+
+                        if click.is_some_and(|position| player_area.contains(position)) {
+                            if let Some(player_state) = &player {
+                                if player_state.sink.is_paused() {
+                                    player_state.sink.play();
+                                } else {
+                                    player_state.sink.pause();
+                                    save_playback_position(
+                                        player_state,
+                                        &mut podcasts,
+                                        &mut stats,
+                                        &data_path,
+                                    )
+                                    .await?;
+                                }
+                            }
+                            return Ok(());
+                        }
+
+                        match view_stack.last() {
+                            None => {
+                                let len = filter_podcasts(&podcasts, &podcast_query).len();
+                                if let Some(index) = click.and_then(|position| {
+                                    clicked_index(
+                                        list_area,
+                                        position,
+                                        0,
+                                        podcast_list_state.offset(),
+                                        len,
+                                    )
+                                }) {
+                                    podcast_list_state.select(Some(index));
+                                }
+                                match scroll {
+                                    Some(true) => podcast_list_state.select_next(),
+                                    Some(false) => podcast_list_state.select_previous(),
+                                    None => {}
                                 }
                             }
-                            _ => {}
-                        },
+                            Some(ViewKind::EpisodeList) => {
+                                let len =
+                                    filter_episodes(&podcasts[current_podcast], &episode_filter)
+                                        .len();
+                                if let Some(index) = click.and_then(|position| {
+                                    clicked_index(list_area, position, 1, episode_list_offset, len)
+                                }) {
+                                    episode_list_table_state.select(Some(index));
+                                }
+                                match scroll {
+                                    Some(true) => episode_list_table_state.select_next(),
+                                    Some(false) => episode_list_table_state.select_previous(),
+                                    None => {}
+                                }
+                            }
+                            Some(ViewKind::Inbox) => {
+                                let len = inbox_episodes(&podcasts, is_inbox_favorites).len();
+                                if let Some(index) = click.and_then(|position| {
+                                    clicked_index(
+                                        list_area,
+                                        position,
+                                        1,
+                                        inbox_table_state.offset(),
+                                        len,
+                                    )
+                                }) {
+                                    inbox_table_state.select(Some(index));
+                                }
+                                match scroll {
+                                    Some(true) => inbox_table_state.select_next(),
+                                    Some(false) => inbox_table_state.select_previous(),
+                                    None => {}
+                                }
+                            }
+                            Some(ViewKind::Queue) => {
+                                if let Some(index) = click.and_then(|position| {
+                                    clicked_index(
+                                        list_area,
+                                        position,
+                                        0,
+                                        queue_list_state.offset(),
+                                        queue.len(),
+                                    )
+                                }) {
+                                    queue_list_state.select(Some(index));
+                                }
+                                match scroll {
+                                    Some(true) => queue_list_state.select_next(),
+                                    Some(false) => queue_list_state.select_previous(),
+                                    None => {}
+                                }
+                            }
+                            Some(
+                                ViewKind::PodcastInfo
+                                | ViewKind::EpisodeInfo
+                                | ViewKind::Transcript
+                                | ViewKind::Stats
+                                | ViewKind::Failures,
+                            ) => match scroll {
+                                Some(true) => {
+                                    info_scroll.offset =
+                                        info_scroll.offset.saturating_add(MOUSE_SCROLL_LINES)
+                                }
+                                Some(false) => {
+                                    info_scroll.offset =
+                                        info_scroll.offset.saturating_sub(MOUSE_SCROLL_LINES)
+                                }
+                                None => {}
+                            },
+                            Some(_) => {}
+                        }
                     }
+                    _ => {}
                 }
-                _ => {}
+
+                Ok(())
             }
+            .await;
+            if let Err(err) = event_result {
+                error_info_popup_state = ErrorInfoPopupState::new(err.to_string());
+                view_stack.push(ViewKind::ErrorInfo);
+            }
+        }
+    }
+
+    let is_in_episode_list = view_stack
+        .iter()
+        .any(|view_kind| matches!(view_kind, ViewKind::EpisodeList));
+    let session = Session {
+        podcast_url: podcast_list_state
+            .selected()
+            .and_then(|selected| {
+                filter_podcasts(&podcasts, &podcast_query)
+                    .get(selected)
+                    .copied()
+            })
+            .map(|index| podcasts[index].url.clone()),
+        episode_key: is_in_episode_list
+            .then(|| podcasts.get(current_podcast))
+            .flatten()
+            .and_then(|podcast| {
+                selected_episode_index(
+                    &episode_list_table_state,
+                    &filter_episodes(podcast, &episode_filter),
+                )
+                .map(|index| podcast.episodes[index].key().to_string())
+            }),
+    };
+    let shutdown_result = async {
+        if let Some(fading) = fading_episode.take() {
+            save_playback_position(&fading.player_state, &mut podcasts, &mut stats, &data_path)
+                .await?;
+            fading.player_state.sink.stop();
+        }
+        if let Some(player_state) = player.take() {
+            save_playback_position(&player_state, &mut podcasts, &mut stats, &data_path).await?;
+            player_state.sink.stop();
         }
+        save_session_to_path(&session, &data_path).await
     }
+    .await;
 
+    _ = execute!(std::io::stdout(), DisableMouseCapture);
     ratatui::restore();
-    Ok(())
+    match loop_error {
+        Some(err) => Err(err.into()),
+        None => shutdown_result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn max_scroll_offset_counts_wrapped_lines() {
+        let lines = vec![
+            Line::from("a".repeat(25)),
+            Line::from(""),
+            Line::from("short"),
+        ];
+        // 3 + 1 + 1 rows at 10 columns.
+        assert_eq!(max_scroll_offset(&lines, 10, 2), 3);
+        assert_eq!(max_scroll_offset(&lines, 10, 5), 0);
+        assert_eq!(max_scroll_offset(&lines, 10, 20), 0);
+        assert_eq!(max_scroll_offset(&lines, 0, 0), 31);
+    }
+
+    #[test]
+    fn parse_timestamp_accepts_minutes_and_hours() {
+        assert_eq!(parse_timestamp("12:34"), Some(Duration::from_secs(754)));
+        assert_eq!(
+            parse_timestamp(" 1:02:03 "),
+            Some(Duration::from_secs(3723))
+        );
+    }
+
+    #[test]
+    fn parse_timestamp_rejects_out_of_range_fields() {
+        assert_eq!(parse_timestamp("12:60"), None);
+        assert_eq!(parse_timestamp("1:60:00"), None);
+        assert_eq!(parse_timestamp("1:00:60"), None);
+        assert_eq!(parse_timestamp("12"), None);
+        assert_eq!(parse_timestamp("a:bc"), None);
+    }
+
+    #[test]
+    fn parse_timestamp_rejects_overflow() {
+        assert_eq!(parse_timestamp("99999999999999999:00:00"), None);
+        assert_eq!(parse_timestamp("999999999999999999:00"), None);
+    }
 }
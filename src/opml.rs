@@ -0,0 +1,69 @@
+use std::path::Path;
+
+use crate::AnyError;
+use crate::podcast::Podcast;
+
+pub async fn import_opml(path: &Path) -> Result<Vec<String>, AnyError> {
+    let text = tokio::fs::read_to_string(path).await?;
+    let doc = roxmltree::Document::parse(&text)?;
+
+    let urls = doc
+        .descendants()
+        .filter(|n| n.has_tag_name("outline"))
+        .filter_map(|n| n.attribute("xmlUrl"))
+        .map(str::trim)
+        .filter(|url| url.starts_with("http://") || url.starts_with("https://"))
+        .map(str::to_string)
+        .collect();
+    Ok(urls)
+}
+
+/// Reads a plain text file with one feed url per line, ignoring blank lines
+/// and `#` comments. Lines are returned as written so invalid ones can be
+/// reported.
+pub async fn import_url_list(path: &Path) -> Result<Vec<String>, AnyError> {
+    let text = tokio::fs::read_to_string(path).await?;
+    Ok(text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(str::to_string)
+        .collect())
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub async fn export_opml(podcasts: &[Podcast], path: &Path) -> Result<(), AnyError> {
+    let mut opml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <opml version=\"2.0\">\n  \
+         <head>\n    <title>Teapod subscriptions</title>\n  </head>\n  \
+         <body>\n",
+    );
+    for podcast in podcasts {
+        let title = escape_xml(&podcast.title);
+        opml.push_str(&format!(
+            "    <outline type=\"rss\" text=\"{}\" title=\"{}\" xmlUrl=\"{}\"/>\n",
+            title,
+            title,
+            escape_xml(&podcast.url)
+        ));
+    }
+    opml.push_str("  </body>\n</opml>\n");
+
+    tokio::fs::write(path, opml).await?;
+    Ok(())
+}
@@ -1,31 +1,540 @@
-use std::path::{Path, PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
 
 use crate::AnyError;
-use chrono::DateTime;
+use crate::credentials::FeedCredentials;
+use crate::http::send_with_retry;
+use crate::stream::{StreamCache, StreamHandle, StreamReader, stream_response};
+use chrono::{DateTime, NaiveDate};
+use image::{ImageReader, RgbImage};
 use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, sync::OwnedMutexGuard};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Podcast {
     pub title: String,
     pub description: String,
     pub url: String,
+    #[serde(default)]
+    pub artwork_url: Option<String>,
+    #[serde(default)]
+    pub link: Option<String>,
+    #[serde(default)]
+    pub validators: FeedValidators,
+    /// Applied to every episode on playback, to even out shows mastered at
+    /// different loudness.
+    #[serde(default)]
+    pub gain_db: f32,
     pub episodes: Vec<Episode>,
+    /// Set while only the summary has been read at startup; `episodes` stays
+    /// empty until `load_podcast_episodes_from_path` fills it in.
+    #[serde(skip)]
+    pub unloaded_counts: Option<EpisodeCounts>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+impl Podcast {
+    pub fn is_loaded(&self) -> bool {
+        self.unloaded_counts.is_none()
+    }
+
+    pub fn episode_counts(&self) -> EpisodeCounts {
+        self.unloaded_counts.unwrap_or_else(|| EpisodeCounts {
+            total: self.episodes.len(),
+            unplayed: self
+                .episodes
+                .iter()
+                .filter(|episode| !episode.played)
+                .count(),
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct EpisodeCounts {
+    pub total: usize,
+    pub unplayed: usize,
+}
+
+/// Everything the podcast list needs, small enough to read for every
+/// subscription at startup.
+#[derive(Serialize, Deserialize)]
+struct PodcastSummary {
+    title: String,
+    description: String,
+    url: String,
+    #[serde(default)]
+    artwork_url: Option<String>,
+    #[serde(default)]
+    link: Option<String>,
+    #[serde(default)]
+    validators: FeedValidators,
+    #[serde(default)]
+    gain_db: f32,
+    counts: EpisodeCounts,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FeedValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl FeedValidators {
+    fn from_headers(headers: &reqwest::header::HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Episode {
     pub title: String,
     pub description: String,
     pub pub_date: String,
     pub url: String,
     pub mime_type: String,
+    #[serde(default)]
+    pub guid: Option<String>,
+    #[serde(default)]
+    pub episode_type: EpisodeType,
+    #[serde(default)]
+    pub position_secs: Option<u64>,
+    #[serde(default)]
+    pub played: bool,
+    #[serde(default)]
+    pub favorite: bool,
+    #[serde(default)]
+    pub explicit: bool,
+    #[serde(default)]
+    pub duration: Option<Duration>,
+    #[serde(default)]
+    pub season: Option<u32>,
+    #[serde(default)]
+    pub number: Option<u32>,
+    #[serde(default)]
+    pub link: Option<String>,
+    #[serde(default)]
+    pub chapters: Vec<Chapter>,
+    /// A Podcast Namespace chapters document, fetched when the episode plays.
+    #[serde(default)]
+    pub chapters_url: Option<String>,
+    #[serde(default)]
+    pub transcript: Option<TranscriptLink>,
+}
+
+impl Episode {
+    pub fn key(&self) -> &str {
+        self.guid.as_deref().unwrap_or(&self.url)
+    }
+
+    pub fn published_date(&self) -> Option<NaiveDate> {
+        self.pub_date.parse().ok()
+    }
+
+    /// Formats the season and episode numbers as `S2E14`, `S2` or `E14`.
+    pub fn numbering(&self) -> Option<String> {
+        match (self.season, self.number) {
+            (Some(season), Some(number)) => Some(format!("S{season}E{number}")),
+            (Some(season), None) => Some(format!("S{season}")),
+            (None, Some(number)) => Some(format!("E{number}")),
+            (None, None) => None,
+        }
+    }
+
+    /// The chapter playing at `position`.
+    pub fn chapter_at(&self, position: Duration) -> Option<&Chapter> {
+        self.chapters
+            .iter()
+            .rev()
+            .find(|chapter| chapter.start <= position)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TranscriptLink {
+    pub url: String,
+    pub mime_type: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Chapter {
+    pub title: String,
+    pub start: Duration,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EpisodeType {
+    #[default]
+    Full,
+    Trailer,
+    Bonus,
+}
+
+impl EpisodeType {
+    fn from_itunes(text: &str) -> Self {
+        match text.trim().to_lowercase().as_str() {
+            "trailer" => Self::Trailer,
+            "bonus" => Self::Bonus,
+            _ => Self::Full,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Full => "",
+            Self::Trailer => "[Trailer] ",
+            Self::Bonus => "[Bonus] ",
+        }
+    }
 }
 
 pub const PODCAST_FEED_FILE: &str = "feed.json";
+pub const PODCAST_SUMMARY_FILE: &str = "summary.json";
+pub const PODCAST_ORDER_FILE: &str = "order.json";
+pub const PODCAST_ARTWORK_FILE: &str = "artwork";
+const ARTWORK_THUMBNAIL_SIZE: u32 = 64;
+
+const ITUNES_NAMESPACE: &str = "http://www.itunes.com/dtds/podcast-1.0.dtd";
+const ATOM_NAMESPACE: &str = "http://www.w3.org/2005/Atom";
+const PSC_NAMESPACE: &str = "http://podlove.org/simple-chapters";
+/// Feeds use both the current namespace url and the original GitHub one.
+const PODCAST_NAMESPACES: [&str; 2] = [
+    "https://podcastindex.org/namespace/1.0",
+    "https://github.com/Podcastindex-org/podcast-namespace/blob/main/docs/1.0.md",
+];
+const UNTITLED: &str = "(untitled)";
+const DEFAULT_MIME_TYPE: &str = "audio/mpeg";
+
+fn sanitize_filename(name: &str) -> String {
+    let sanitized = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect::<String>();
+    let sanitized = sanitized
+        .trim_start_matches('.')
+        .trim_end_matches(['.', ' ']);
+    if sanitized.trim().is_empty() {
+        "_".to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+pub fn podcast_dir(podcast: &Podcast, path: &Path) -> PathBuf {
+    path.join(sanitize_filename(&podcast.title))
+}
+
+fn parse_itunes_duration(text: &str) -> Option<Duration> {
+    let parts = text.trim().split(':').collect::<Vec<_>>();
+    if parts.len() > 3 {
+        return None;
+    }
+
+    let mut seconds = 0.0;
+    for part in parts {
+        seconds = seconds * 60.0 + part.trim().parse::<f64>().ok()?;
+    }
+    (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds))
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    if let Some(number) = entity.strip_prefix('#') {
+        let code = match number.strip_prefix(['x', 'X']) {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => number.parse().ok()?,
+        };
+        return char::from_u32(code);
+    }
+
+    Some(match entity {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "nbsp" => ' ',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "ndash" => '–',
+        "mdash" => '—',
+        "hellip" => '…',
+        "copy" => '©',
+        _ => return None,
+    })
+}
+
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest
+            .find(';')
+            .filter(|&end| end <= 10)
+            .and_then(|end| Some((end, decode_entity(&rest[1..end])?)))
+        {
+            Some((end, c)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Trims and collapses whitespace on every line, keeping at most one blank
+/// line between paragraphs.
+fn collapse_whitespace(text: &str) -> String {
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .fold(Vec::<String>::new(), |mut lines, line| {
+            if !line.is_empty() || lines.last().is_some_and(|last| !last.is_empty()) {
+                lines.push(line);
+            }
+            lines
+        })
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+pub fn clean_description(text: &str) -> String {
+    let mut stripped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        stripped.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+
+        let tag = rest[start + 1..start + end]
+            .trim_start_matches('/')
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        if matches!(
+            tag.as_str(),
+            "br" | "p" | "div" | "li" | "h1" | "h2" | "h3" | "h4"
+        ) {
+            stripped.push('\n');
+        }
+        rest = &rest[start + end + 1..];
+    }
+    stripped.push_str(rest);
+
+    collapse_whitespace(&decode_entities(&stripped))
+}
+
+/// Reads the plain RSS `<link>`, skipping the `atom:link` self references many
+/// feeds put next to it.
+fn parse_rss_link(node: roxmltree::Node) -> Option<String> {
+    node.children()
+        .find(|n| n.has_tag_name("link") && n.tag_name().namespace().is_none())
+        .map(node_text)
+        .map(|link| link.trim().to_string())
+        .filter(|link| !link.is_empty())
+}
+
+fn parse_atom_link(node: roxmltree::Node) -> Option<String> {
+    node.children()
+        .find(|n| {
+            n.has_tag_name((ATOM_NAMESPACE, "link"))
+                && n.attribute("rel").is_none_or(|rel| rel == "alternate")
+        })
+        .and_then(|n| n.attribute("href"))
+        .map(str::to_string)
+}
+
+/// Reads Podlove Simple Chapters, whose start times look like `01:02:03.500`.
+fn parse_psc_chapters(node: roxmltree::Node) -> Vec<Chapter> {
+    let mut chapters = node
+        .children()
+        .filter(|n| n.has_tag_name((PSC_NAMESPACE, "chapters")))
+        .flat_map(|n| n.children())
+        .filter(|n| n.has_tag_name((PSC_NAMESPACE, "chapter")))
+        .filter_map(|n| {
+            Some(Chapter {
+                title: clean_title(n.attribute("title").unwrap_or_default()),
+                start: parse_itunes_duration(n.attribute("start")?)?,
+            })
+        })
+        .collect::<Vec<_>>();
+    chapters.sort_by_key(|chapter| chapter.start);
+    chapters
+}
+
+fn parse_chapters_url(node: roxmltree::Node) -> Option<String> {
+    node.children()
+        .find(|n| {
+            PODCAST_NAMESPACES
+                .iter()
+                .any(|namespace| n.has_tag_name((*namespace, "chapters")))
+        })
+        .and_then(|n| n.attribute("url"))
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty())
+}
+
+/// Feeds often offer the same transcript in several formats; this picks the
+/// one that reads best as text and ignores formats that can't be shown.
+fn parse_transcript_link(node: roxmltree::Node) -> Option<TranscriptLink> {
+    node.children()
+        .filter(|n| {
+            PODCAST_NAMESPACES
+                .iter()
+                .any(|namespace| n.has_tag_name((*namespace, "transcript")))
+        })
+        .filter_map(|n| {
+            let url = n.attribute("url")?.trim();
+            let mime_type = n.attribute("type")?.trim().to_lowercase();
+            let rank = transcript_extension(&mime_type)?.1;
+            (!url.is_empty()).then(|| {
+                (
+                    rank,
+                    TranscriptLink {
+                        url: url.to_string(),
+                        mime_type,
+                    },
+                )
+            })
+        })
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, link)| link)
+}
+
+/// The cache file extension and the preference order of a transcript format.
+fn transcript_extension(mime_type: &str) -> Option<(&'static str, u8)> {
+    match mime_type {
+        "text/plain" => Some(("txt", 0)),
+        "text/vtt" => Some(("vtt", 1)),
+        "application/srt" | "application/x-subrip" | "text/srt" => Some(("srt", 2)),
+        "text/html" => Some(("html", 3)),
+        _ => None,
+    }
+}
+
+fn parse_itunes_number(node: roxmltree::Node, name: &str) -> Option<u32> {
+    node.children()
+        .find(|n| n.has_tag_name((ITUNES_NAMESPACE, name)))
+        .and_then(|n| n.text())
+        .and_then(|text| text.trim().parse().ok())
+}
+
+/// Returns `None` when `node` has no `itunes:explicit` tag, so an item can fall
+/// back to its channel's rating.
+fn parse_itunes_explicit(node: roxmltree::Node) -> Option<bool> {
+    let text = node
+        .children()
+        .find(|n| n.has_tag_name((ITUNES_NAMESPACE, "explicit")))?
+        .text()?
+        .trim()
+        .to_lowercase();
+    Some(matches!(text.as_str(), "true" | "yes" | "explicit"))
+}
+
+/// Concatenates every text node under `node`. Unlike `Node::text`, this keeps
+/// text that follows a comment or nested element, and CDATA sections are
+/// already text nodes in roxmltree.
+fn node_text(node: roxmltree::Node) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect()
+}
+
+/// Text the parser has already decoded, flattened onto one line.
+fn clean_title(text: &str) -> String {
+    let title = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() {
+        UNTITLED.to_string()
+    } else {
+        title
+    }
+}
+
+/// Markup reaches a title or description either in CDATA or, in Atom, as
+/// `type="html"` text. Anything else was decoded by the parser already, and
+/// stripping tags or decoding entities again would eat a literal `<T>` or
+/// `&amp;`.
+fn is_html(node: roxmltree::Node) -> bool {
+    node.attribute("type") == Some("html")
+        || node.document().input_text()[node.range()].contains("<![CDATA[")
+}
+
+fn title_text(node: roxmltree::Node) -> String {
+    let text = node_text(node);
+    if is_html(node) {
+        clean_title(&clean_description(&text))
+    } else {
+        clean_title(&text)
+    }
+}
+
+/// Unlike titles, descriptions are also commonly sent as entity-escaped HTML,
+/// so decoded text that still holds tags is cleaned up as HTML too.
+fn description_text(node: roxmltree::Node) -> String {
+    let text = node_text(node);
+    if is_html(node) || contains_tags(&text) {
+        clean_description(&text)
+    } else {
+        collapse_whitespace(&text)
+    }
+}
 
-fn parse_podcast_info_from_rss(text: &str, url: &str) -> Result<Podcast, AnyError> {
+fn contains_tags(text: &str) -> bool {
+    text.match_indices('<').any(|(start, _)| {
+        let rest = &text[start + 1..];
+        rest.starts_with(|c: char| c.is_ascii_alphabetic() || c == '/' || c == '!')
+            && rest.contains('>')
+    })
+}
+
+fn parse_pub_date(text: &str) -> Option<NaiveDate> {
+    let text = text.trim();
+    DateTime::parse_from_rfc2822(text)
+        .or_else(|_| DateTime::parse_from_rfc3339(text))
+        .map(|date| date.date_naive())
+        .or_else(|_| NaiveDate::parse_from_str(text, "%Y-%m-%d"))
+        .ok()
+}
+
+fn parse_podcast_info(text: &str, url: &str) -> Result<Podcast, AnyError> {
     let doc = roxmltree::Document::parse(text)?;
+    let root = doc.root_element();
+    if root.has_tag_name((ATOM_NAMESPACE, "feed")) {
+        parse_podcast_info_from_atom(root, url)
+    } else {
+        parse_podcast_info_from_rss(&doc, url)
+    }
+}
 
+fn parse_podcast_info_from_rss(doc: &roxmltree::Document, url: &str) -> Result<Podcast, AnyError> {
     let channel = doc
         .descendants()
         .find(|n| n.has_tag_name("channel"))
@@ -34,131 +543,1356 @@ fn parse_podcast_info_from_rss(text: &str, url: &str) -> Result<Podcast, AnyErro
     let title = channel
         .children()
         .find(|n| n.has_tag_name("title"))
-        .ok_or("missing title tag")?
-        .text()
-        .unwrap_or_default()
-        .to_string();
+        .map_or_else(|| UNTITLED.to_string(), title_text);
     let description = channel
         .children()
         .find(|n| n.has_tag_name("description"))
-        .ok_or("missing description tag")?
-        .text()
-        .unwrap_or_default()
-        .to_string();
+        .map(description_text)
+        .unwrap_or_default();
     let url = url.to_string();
+    let artwork_url = channel
+        .children()
+        .find(|n| n.has_tag_name((ITUNES_NAMESPACE, "image")))
+        .and_then(|n| n.attribute("href"))
+        .or_else(|| {
+            channel
+                .children()
+                .find(|n| n.has_tag_name("image"))?
+                .children()
+                .find(|n| n.has_tag_name("url"))?
+                .text()
+        })
+        .map(|artwork_url| artwork_url.trim().to_string());
+    let explicit = parse_itunes_explicit(channel).unwrap_or(false);
 
     let mut episodes = Vec::new();
     for item in channel.children().filter(|n| n.has_tag_name("item")) {
+        let Some(enclosure) = item.children().find(|n| n.has_tag_name("enclosure")) else {
+            continue;
+        };
+        let Some(url) = enclosure.attribute("url") else {
+            continue;
+        };
+        let url = url.to_string();
+        let mime_type = enclosure
+            .attribute("type")
+            .unwrap_or(DEFAULT_MIME_TYPE)
+            .to_string();
+
         let title = item
             .children()
             .find(|n| n.has_tag_name("title"))
-            .ok_or("missing title tag")?
-            .text()
-            .unwrap_or_default()
-            .to_string();
+            .map_or_else(|| UNTITLED.to_string(), title_text);
         let description = item
             .children()
             .find(|n| n.has_tag_name("description"))
-            .ok_or("missing description tag")?
-            .text()
-            .unwrap_or_default()
-            .to_string();
-        let pub_date = DateTime::parse_from_rfc2822(
-            item.children()
-                .find(|n| n.has_tag_name("pubDate"))
-                .ok_or("missing pubDate tag")?
-                .text()
-                .unwrap_or_default(),
-        )?
-        .date_naive()
-        .to_string();
+            .map(description_text)
+            .unwrap_or_default();
+        let pub_date = item
+            .children()
+            .find(|n| n.has_tag_name("pubDate"))
+            .and_then(|n| n.text())
+            .and_then(parse_pub_date)
+            .map(|date| date.to_string())
+            .unwrap_or_default();
+        let episode_type = item
+            .children()
+            .find(|n| n.has_tag_name((ITUNES_NAMESPACE, "episodeType")))
+            .and_then(|n| n.text())
+            .map(EpisodeType::from_itunes)
+            .unwrap_or_default();
+        let duration = item
+            .children()
+            .find(|n| n.has_tag_name((ITUNES_NAMESPACE, "duration")))
+            .and_then(|n| n.text())
+            .and_then(parse_itunes_duration);
+        let season = parse_itunes_number(item, "season");
+        let number = parse_itunes_number(item, "episode");
+        let explicit = parse_itunes_explicit(item).unwrap_or(explicit);
+        let link = parse_rss_link(item);
+        let chapters = parse_psc_chapters(item);
+        let chapters_url = parse_chapters_url(item);
+        let transcript = parse_transcript_link(item);
 
-        let enclosure = item
+        let guid = item
             .children()
-            .find(|n| n.has_tag_name("enclosure"))
-            .ok_or("missing enclosure tag")?;
-        let url = enclosure
-            .attribute("url")
-            .ok_or("missing url attr")?
-            .to_string();
-        let mime_type = enclosure
-            .attribute("type")
-            .ok_or("missing type attr")?
-            .to_string();
+            .find(|n| n.has_tag_name("guid"))
+            .and_then(|n| n.text())
+            .map(str::trim)
+            .filter(|guid| !guid.is_empty())
+            .map(str::to_string);
 
-        episodes.push(Episode {
+        let episode = Episode {
             title,
             description,
             pub_date,
             url,
             mime_type,
-        });
+            guid,
+            episode_type,
+            position_secs: None,
+            played: false,
+            favorite: false,
+            explicit,
+            duration,
+            season,
+            number,
+            link,
+            chapters,
+            chapters_url,
+            transcript,
+        };
+        if !episodes
+            .iter()
+            .any(|other: &Episode| other.key() == episode.key())
+        {
+            episodes.push(episode);
+        }
     }
 
     Ok(Podcast {
         title,
         description,
         url,
+        artwork_url,
+        link: parse_rss_link(channel),
+        validators: FeedValidators::default(),
+        gain_db: 0.0,
         episodes,
+        unloaded_counts: None,
     })
 }
 
-pub async fn download_podcast_info_from_url(url: &str) -> Result<Podcast, AnyError> {
-    let res = reqwest::get(url).await?;
+fn atom_child<'a, 'input>(
+    node: roxmltree::Node<'a, 'input>,
+    name: &str,
+) -> Option<roxmltree::Node<'a, 'input>> {
+    node.children()
+        .find(|n| n.has_tag_name((ATOM_NAMESPACE, name)))
+}
+
+fn atom_child_text(node: roxmltree::Node, name: &str) -> Option<String> {
+    atom_child(node, name)
+        .map(node_text)
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+fn parse_podcast_info_from_atom(feed: roxmltree::Node, url: &str) -> Result<Podcast, AnyError> {
+    let title = atom_child(feed, "title").map_or_else(|| UNTITLED.to_string(), title_text);
+    let description = atom_child(feed, "subtitle")
+        .map(description_text)
+        .unwrap_or_default();
+    let artwork_url = feed
+        .children()
+        .find(|n| n.has_tag_name((ITUNES_NAMESPACE, "image")))
+        .and_then(|n| n.attribute("href"))
+        .map(str::to_string)
+        .or_else(|| atom_child_text(feed, "logo"))
+        .or_else(|| atom_child_text(feed, "icon"))
+        .map(|artwork_url| artwork_url.trim().to_string());
+    let explicit = parse_itunes_explicit(feed).unwrap_or(false);
+
+    let mut episodes = Vec::new();
+    for entry in feed
+        .children()
+        .filter(|n| n.has_tag_name((ATOM_NAMESPACE, "entry")))
+    {
+        let Some(enclosure) = entry.children().find(|n| {
+            n.has_tag_name((ATOM_NAMESPACE, "link")) && n.attribute("rel") == Some("enclosure")
+        }) else {
+            continue;
+        };
+        let Some(url) = enclosure.attribute("href") else {
+            continue;
+        };
+
+        let episode = Episode {
+            title: atom_child(entry, "title").map_or_else(|| UNTITLED.to_string(), title_text),
+            description: atom_child(entry, "summary")
+                .map(description_text)
+                .filter(|description| !description.is_empty())
+                .or_else(|| atom_child(entry, "content").map(description_text))
+                .unwrap_or_default(),
+            pub_date: atom_child_text(entry, "published")
+                .or_else(|| atom_child_text(entry, "updated"))
+                .as_deref()
+                .and_then(parse_pub_date)
+                .map(|date| date.to_string())
+                .unwrap_or_default(),
+            url: url.to_string(),
+            mime_type: enclosure
+                .attribute("type")
+                .unwrap_or(DEFAULT_MIME_TYPE)
+                .to_string(),
+            guid: atom_child_text(entry, "id"),
+            episode_type: entry
+                .children()
+                .find(|n| n.has_tag_name((ITUNES_NAMESPACE, "episodeType")))
+                .and_then(|n| n.text())
+                .map(EpisodeType::from_itunes)
+                .unwrap_or_default(),
+            position_secs: None,
+            played: false,
+            favorite: false,
+            explicit: parse_itunes_explicit(entry).unwrap_or(explicit),
+            duration: entry
+                .children()
+                .find(|n| n.has_tag_name((ITUNES_NAMESPACE, "duration")))
+                .and_then(|n| n.text())
+                .and_then(parse_itunes_duration),
+            season: parse_itunes_number(entry, "season"),
+            number: parse_itunes_number(entry, "episode"),
+            link: parse_atom_link(entry),
+            chapters: parse_psc_chapters(entry),
+            chapters_url: parse_chapters_url(entry),
+            transcript: parse_transcript_link(entry),
+        };
+        if !episodes
+            .iter()
+            .any(|other: &Episode| other.key() == episode.key())
+        {
+            episodes.push(episode);
+        }
+    }
+
+    Ok(Podcast {
+        title,
+        description,
+        url: url.to_string(),
+        artwork_url,
+        link: parse_atom_link(feed),
+        validators: FeedValidators::default(),
+        gain_db: 0.0,
+        episodes,
+        unloaded_counts: None,
+    })
+}
+
+fn html_attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut offset = 0;
+    while let Some(found) = lower[offset..].find(name) {
+        let start = offset + found;
+        offset = start + name.len();
+        if !lower[..start].ends_with(char::is_whitespace) {
+            continue;
+        }
+
+        let rest = tag[offset..].trim_start();
+        let Some(rest) = rest.strip_prefix('=') else {
+            continue;
+        };
+        let rest = rest.trim_start();
+        return match rest.chars().next()? {
+            quote @ ('"' | '\'') => rest[1..].split(quote).next(),
+            _ => rest.split(|c: char| c.is_whitespace() || c == '>').next(),
+        };
+    }
+    None
+}
+
+fn find_feed_link(html: &str, base_url: &reqwest::Url) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    lower.match_indices("<link").find_map(|(start, _)| {
+        let end = start + lower[start..].find('>')?;
+        let tag = &html[start..end];
+        let rel = html_attribute(tag, "rel")?.to_ascii_lowercase();
+        let link_type = html_attribute(tag, "type")?.to_ascii_lowercase();
+        if !rel.split_whitespace().any(|rel| rel == "alternate")
+            || !matches!(
+                link_type.as_str(),
+                "application/rss+xml" | "application/atom+xml" | "application/xml" | "text/xml"
+            )
+        {
+            return None;
+        }
+        let href = html_attribute(tag, "href")?;
+        base_url.join(href).ok().map(String::from)
+    })
+}
+
+fn looks_like_html(content_type: &str, text: &str) -> bool {
+    content_type.contains("text/html") || {
+        let text = text.trim_start();
+        let head = text.get(..256).unwrap_or(text).to_ascii_lowercase();
+        head.starts_with("<!doctype html") || head.starts_with("<html")
+    }
+}
+
+fn response_content_type(res: &reqwest::Response) -> String {
+    res.headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Rejects bodies that can't be a feed before handing them to the XML parser,
+/// so an error page served with a 200 status is reported as such rather than
+/// as a missing channel tag.
+fn parse_feed_response(text: &str, content_type: &str, url: &str) -> Result<Podcast, AnyError> {
+    if looks_like_html(content_type, text) {
+        return Err(format!("{url} returned a web page instead of a podcast feed").into());
+    }
+    if !text
+        .trim_start_matches('\u{feff}')
+        .trim_start()
+        .starts_with('<')
+    {
+        let content_type = match content_type {
+            "" => "no content type",
+            content_type => content_type,
+        };
+        return Err(format!("{url} did not return a podcast feed ({content_type})").into());
+    }
+    parse_podcast_info(text, url)
+}
+
+/// Returns `None` when the server reports the feed unchanged since `validators`.
+///
+/// The returned podcast's `url` is the one the feed was finally served from,
+/// after following redirects or a feed link on a web page.
+pub async fn download_podcast_info_from_url(
+    client: &reqwest::Client,
+    url: &str,
+    validators: &FeedValidators,
+    credentials: Option<&FeedCredentials>,
+) -> Result<Option<Podcast>, AnyError> {
+    let authorize = |req: reqwest::RequestBuilder| match credentials {
+        Some(credentials) => req.basic_auth(&credentials.username, Some(&credentials.password)),
+        None => req,
+    };
+
+    let mut req = authorize(client.get(url));
+    if let Some(etag) = &validators.etag {
+        req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        req = req.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+    }
+    let res = send_with_retry(req).await?;
+    if res.status() == reqwest::StatusCode::UNAUTHORIZED {
+        return Err(match credentials {
+            Some(_) => format!("credentials rejected by {url}").into(),
+            None => format!("{url} requires a username and password").into(),
+        });
+    }
+    if res.status() == reqwest::StatusCode::NOT_MODIFIED {
+        return Ok(None);
+    }
+
+    let res = res.error_for_status()?;
+    let validators = FeedValidators::from_headers(res.headers());
+    let page_url = res.url().clone();
+    let content_type = response_content_type(&res);
     let text = res.text().await?;
-    parse_podcast_info_from_rss(&text, url)
+    if !looks_like_html(&content_type, &text) {
+        let mut podcast = parse_feed_response(&text, &content_type, page_url.as_str())?;
+        podcast.validators = validators;
+        return Ok(Some(podcast));
+    }
+
+    match find_feed_link(&text, &page_url) {
+        Some(feed_url) => {
+            let res = send_with_retry(authorize(client.get(&feed_url)))
+                .await?
+                .error_for_status()?;
+            let validators = FeedValidators::from_headers(res.headers());
+            let feed_url = res.url().to_string();
+            let content_type = response_content_type(&res);
+            let mut podcast = parse_feed_response(&res.text().await?, &content_type, &feed_url)?;
+            podcast.validators = validators;
+            Ok(Some(podcast))
+        }
+        None => parse_podcast_info(&text, page_url.as_str())
+            .map(Some)
+            .map_err(|_| format!("no podcast feed found at {page_url}").into()),
+    }
+}
+
+async fn save_podcast_summary_to_dir(podcast: &Podcast, feed_dir: &Path) -> Result<(), AnyError> {
+    let summary = PodcastSummary {
+        title: podcast.title.clone(),
+        description: podcast.description.clone(),
+        url: podcast.url.clone(),
+        artwork_url: podcast.artwork_url.clone(),
+        link: podcast.link.clone(),
+        validators: podcast.validators.clone(),
+        gain_db: podcast.gain_db,
+        counts: podcast.episode_counts(),
+    };
+    let json = serde_json::to_string(&summary)?;
+    tokio::fs::write(feed_dir.join(PODCAST_SUMMARY_FILE), json).await?;
+    Ok(())
 }
 
 pub async fn save_podcast_info_to_path(podcast: &Podcast, path: &Path) -> Result<(), AnyError> {
-    let feed_dir = path.join(&podcast.title);
-    if !feed_dir.exists() {
-        tokio::fs::create_dir(&feed_dir).await?;
+    // Saving an unloaded podcast would overwrite its feed with no episodes.
+    if !podcast.is_loaded() {
+        return Err(format!("episodes of {} are not loaded", podcast.title).into());
     }
 
+    let feed_dir = podcast_dir(podcast, path);
+    tokio::fs::create_dir_all(&feed_dir).await?;
+
     let feed_file = feed_dir.join(PODCAST_FEED_FILE);
     let json = serde_json::to_string(podcast)?;
     tokio::fs::write(feed_file, json).await?;
+    save_podcast_summary_to_dir(podcast, &feed_dir).await
+}
+
+/// Reads the podcast in `feed_dir` without its episodes. Libraries saved
+/// before summaries existed are read in full once and given a summary.
+pub async fn load_podcast_summary_from_dir(feed_dir: &Path) -> Result<Option<Podcast>, AnyError> {
+    let summary_file = feed_dir.join(PODCAST_SUMMARY_FILE);
+    if summary_file.exists() {
+        let json = tokio::fs::read_to_string(summary_file).await?;
+        let summary: PodcastSummary = serde_json::from_str(&json)?;
+        return Ok(Some(Podcast {
+            title: summary.title,
+            description: summary.description,
+            url: summary.url,
+            artwork_url: summary.artwork_url,
+            link: summary.link,
+            validators: summary.validators,
+            gain_db: summary.gain_db,
+            episodes: Vec::new(),
+            unloaded_counts: Some(summary.counts),
+        }));
+    }
+
+    let feed_file = feed_dir.join(PODCAST_FEED_FILE);
+    if !feed_file.exists() {
+        return Ok(None);
+    }
+    let json = tokio::fs::read_to_string(feed_file).await?;
+    let podcast: Podcast = serde_json::from_str(&json)?;
+    save_podcast_summary_to_dir(&podcast, feed_dir).await?;
+    Ok(Some(podcast))
+}
+
+pub async fn load_podcast_episodes_from_path(
+    podcast: &mut Podcast,
+    path: &Path,
+) -> Result<(), AnyError> {
+    if podcast.is_loaded() {
+        return Ok(());
+    }
+
+    let feed_file = podcast_dir(podcast, path).join(PODCAST_FEED_FILE);
+    let json = tokio::fs::read_to_string(feed_file).await?;
+    let loaded: Podcast = serde_json::from_str(&json)?;
+    podcast.episodes = loaded.episodes;
+    podcast.unloaded_counts = None;
     Ok(())
 }
 
-pub async fn update_all_podcast_info(
-    urls: &Vec<&str>,
+/// The url the artwork was downloaded from is kept next to it, so a feed that
+/// changes its artwork gets the new image on its next update.
+pub async fn download_podcast_artwork_to_path(
+    client: &reqwest::Client,
+    podcast: &Podcast,
     path: &Path,
-) -> Result<Vec<Podcast>, AnyError> {
-    let mut podcasts = Vec::new();
-    for url in urls {
-        let podcast = download_podcast_info_from_url(*url).await?;
-        save_podcast_info_to_path(&podcast, path).await?;
-        podcasts.push(podcast);
+) -> Result<(), AnyError> {
+    let Some(artwork_url) = &podcast.artwork_url else {
+        return Ok(());
+    };
+
+    let artwork_file = podcast_dir(podcast, path).join(PODCAST_ARTWORK_FILE);
+    let url_file = suffixed_path(&artwork_file, ".url");
+    if artwork_file.exists()
+        && tokio::fs::read_to_string(&url_file)
+            .await
+            .is_ok_and(|url| url == *artwork_url)
+    {
+        return Ok(());
     }
 
-    Ok(podcasts)
+    let res = send_with_retry(client.get(artwork_url))
+        .await?
+        .error_for_status()?;
+    let contents = res.bytes().await?;
+    tokio::fs::create_dir_all(podcast_dir(podcast, path)).await?;
+    // Written aside and renamed, so an interrupted download never leaves a
+    // truncated image in place.
+    let part_file = suffixed_path(&artwork_file, ".part");
+    tokio::fs::write(&part_file, contents).await?;
+    tokio::fs::rename(&part_file, &artwork_file).await?;
+    tokio::fs::write(url_file, artwork_url).await?;
+    Ok(())
+}
+
+#[derive(Deserialize)]
+struct ChaptersDocument {
+    chapters: Vec<ChaptersDocumentEntry>,
 }
 
-pub async fn download_podcast_audio_to_path(
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChaptersDocumentEntry {
+    start_time: f64,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    toc: Option<bool>,
+}
+
+/// Fetches a Podcast Namespace JSON chapters document. Chapters marked
+/// `"toc": false` are meant to be hidden, so they're skipped.
+pub async fn download_chapters_from_url(
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<Vec<Chapter>, AnyError> {
+    let res = send_with_retry(client.get(url)).await?.error_for_status()?;
+    let document: ChaptersDocument = serde_json::from_str(&res.text().await?)?;
+    let mut chapters = document
+        .chapters
+        .into_iter()
+        .filter(|entry| entry.toc != Some(false))
+        .filter(|entry| entry.start_time.is_finite() && entry.start_time >= 0.0)
+        .map(|entry| Chapter {
+            title: clean_title(&entry.title.unwrap_or_default()),
+            start: Duration::from_secs_f64(entry.start_time),
+        })
+        .collect::<Vec<_>>();
+    chapters.sort_by_key(|chapter| chapter.start);
+    Ok(chapters)
+}
+
+/// Asks the server how big the episode's audio is without downloading it.
+/// Returns `None` when the server doesn't say.
+pub async fn fetch_podcast_audio_size(
+    client: &reqwest::Client,
+    episode: &Episode,
+) -> Result<Option<u64>, AnyError> {
+    let res = send_with_retry(client.head(&episode.url))
+        .await?
+        .error_for_status()?;
+    // Read the header directly, since a HEAD response has no body for
+    // `content_length` to describe.
+    Ok(res
+        .headers()
+        .get(reqwest::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok()))
+}
+
+/// Returns the transcript text, downloading it next to the episode's audio the
+/// first time.
+pub async fn download_podcast_transcript_to_path(
+    client: &reqwest::Client,
     podcast: &Podcast,
     episode: &Episode,
     path: &Path,
-) -> Result<PathBuf, AnyError> {
-    let mut audio_file = path.join(&podcast.title).join(&episode.title);
-    match episode.mime_type.as_str() {
-        "audio/mpeg" => {
-            audio_file = audio_file.with_extension("mp3");
-            if !audio_file.exists() {
-                let res = reqwest::get(&episode.url).await?;
-                let contents = res.bytes().await?;
-                tokio::fs::write(&audio_file, contents).await?;
+) -> Result<String, AnyError> {
+    let link = episode
+        .transcript
+        .as_ref()
+        .ok_or("this episode has no transcript")?;
+    let (extension, _) = transcript_extension(&link.mime_type)
+        .ok_or_else(|| format!("transcript format {} is not supported", link.mime_type))?;
+    let transcript_file = podcast_dir(podcast, path).join(format!(
+        "{:016x}.transcript.{}",
+        fnv1a_hash(episode.key()),
+        extension
+    ));
+    if transcript_file.exists() {
+        return Ok(tokio::fs::read_to_string(transcript_file).await?);
+    }
+
+    let text = send_with_retry(client.get(&link.url))
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    tokio::fs::create_dir_all(podcast_dir(podcast, path)).await?;
+    tokio::fs::write(transcript_file, &text).await?;
+    Ok(text)
+}
+
+pub fn load_podcast_artwork_from_path(podcast: &Podcast, path: &Path) -> Option<RgbImage> {
+    let artwork_file = podcast_dir(podcast, path).join(PODCAST_ARTWORK_FILE);
+    let image = ImageReader::open(artwork_file)
+        .ok()?
+        .with_guessed_format()
+        .ok()?
+        .decode()
+        .ok()?;
+    Some(
+        image
+            .thumbnail(ARTWORK_THUMBNAIL_SIZE, ARTWORK_THUMBNAIL_SIZE)
+            .to_rgb8(),
+    )
+}
+
+pub async fn load_podcast_order_from_path(path: &Path) -> Result<Vec<String>, AnyError> {
+    let order_file = path.join(PODCAST_ORDER_FILE);
+    if !order_file.exists() {
+        return Ok(Vec::new());
+    }
+
+    let json = tokio::fs::read_to_string(order_file).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub async fn save_podcast_order_to_path(podcasts: &[Podcast], path: &Path) -> Result<(), AnyError> {
+    let order = podcasts
+        .iter()
+        .map(|podcast| podcast.url.as_str())
+        .collect::<Vec<_>>();
+    let json = serde_json::to_string(&order)?;
+    tokio::fs::write(path.join(PODCAST_ORDER_FILE), json).await?;
+    Ok(())
+}
+
+/// Returns the number of episodes that weren't in `old_podcast`.
+/// Episodes that dropped out of the feed are kept only while they still carry
+/// state, so the list doesn't grow without bound.
+pub fn merge_episode_state(
+    podcast: &mut Podcast,
+    old_podcast: &Podcast,
+    path: &Path,
+    filename_template: &str,
+) -> usize {
+    let old_episodes = old_podcast
+        .episodes
+        .iter()
+        .map(|old_episode| (old_episode.key(), old_episode))
+        .collect::<HashMap<_, _>>();
+
+    let mut new_episodes = 0;
+    for episode in podcast.episodes.iter_mut() {
+        if let Some(old_episode) = old_episodes.get(episode.key()) {
+            episode.played = old_episode.played;
+            episode.position_secs = old_episode.position_secs;
+            episode.favorite = old_episode.favorite;
+            // Chapters fetched from `chapters_url` aren't part of the feed itself.
+            if episode.chapters.is_empty() && episode.chapters_url == old_episode.chapters_url {
+                episode.chapters = old_episode.chapters.clone();
             }
+        } else {
+            new_episodes += 1;
+        }
+    }
+
+    let keys = podcast
+        .episodes
+        .iter()
+        .map(Episode::key)
+        .collect::<HashSet<_>>();
+    let dropped_episodes = old_podcast
+        .episodes
+        .iter()
+        .filter(|old_episode| {
+            !keys.contains(old_episode.key())
+                && (old_episode.played
+                    || old_episode.favorite
+                    || old_episode.position_secs.is_some()
+                    || check_podcast_audio_in_path(
+                        old_podcast,
+                        old_episode,
+                        path,
+                        filename_template,
+                    ))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    podcast.episodes.extend(dropped_episodes);
+    new_episodes
+}
+
+fn suffixed_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut suffixed = path.as_os_str().to_owned();
+    suffixed.push(suffix);
+    PathBuf::from(suffixed)
+}
+
+const AUDIO_EXTENSIONS: [&str; 7] = ["mp3", "m4a", "aac", "ogg", "opus", "flac", "wav"];
+
+fn audio_extension(mime_type: &str) -> Option<&'static str> {
+    match mime_type.trim().to_lowercase().as_str() {
+        "audio/mpeg" | "audio/mp3" | "audio/mpeg3" => Some("mp3"),
+        "audio/mp4" | "audio/x-m4a" | "audio/m4a" => Some("m4a"),
+        "audio/aac" | "audio/x-aac" => Some("aac"),
+        "audio/ogg" | "audio/vorbis" => Some("ogg"),
+        "audio/opus" => Some("opus"),
+        "audio/flac" | "audio/x-flac" => Some("flac"),
+        "audio/wav" | "audio/x-wav" => Some("wav"),
+        _ => None,
+    }
+}
+
+fn fnv1a_hash(text: &str) -> u64 {
+    text.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Exports are meant to be browsed by people, so they're always named readably.
+const EXPORT_FILENAME_TEMPLATE: &str = "{podcast} - {date} - {title}";
+
+/// Names downloads by a hash of the episode key, which never collides and
+/// matches the files cached before filename templates existed.
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "{hash}";
+
+/// Expands `template` into a path relative to the podcast's directory. Each
+/// `/`-separated part is sanitized on its own, so templates can sort downloads
+/// into subdirectories.
+fn render_filename_template(template: &str, podcast: &Podcast, episode: &Episode) -> PathBuf {
+    let number = |number: Option<u32>| number.map(|number| number.to_string());
+    let date = episode
+        .published_date()
+        .map_or_else(|| "undated".to_string(), |date| date.to_string());
+    let fields = [
+        ("{podcast}", podcast.title.clone()),
+        ("{title}", episode.title.clone()),
+        ("{date}", date),
+        ("{season}", number(episode.season).unwrap_or_default()),
+        ("{episode}", number(episode.number).unwrap_or_default()),
+        ("{hash}", format!("{:016x}", fnv1a_hash(episode.key()))),
+    ];
+    template
+        .split('/')
+        .map(|part| {
+            fields
+                .iter()
+                .fold(part.to_string(), |part, (field, value)| {
+                    part.replace(field, value)
+                })
+        })
+        .filter(|part| !part.trim().is_empty())
+        .map(|part| sanitize_filename(&part))
+        .collect()
+}
+
+/// Returns the audio file's path relative to the podcast's directory.
+fn podcast_audio_file_name(
+    podcast: &Podcast,
+    episode: &Episode,
+    filename_template: &str,
+) -> Result<PathBuf, AnyError> {
+    let extension = audio_extension(&episode.mime_type)
+        .ok_or_else(|| format!("audio format {} is not supported", episode.mime_type))?;
+    let mut file_name = render_filename_template(filename_template, podcast, episode);
+    if file_name.as_os_str().is_empty() {
+        file_name = render_filename_template(DEFAULT_FILENAME_TEMPLATE, podcast, episode);
+    }
+    let mut file_name = file_name.into_os_string();
+    file_name.push(format!(".{extension}"));
+    Ok(file_name.into())
+}
+
+fn podcast_audio_path(
+    podcast: &Podcast,
+    episode: &Episode,
+    path: &Path,
+    filename_template: &str,
+) -> Result<PathBuf, AnyError> {
+    Ok(podcast_dir(podcast, path).join(podcast_audio_file_name(
+        podcast,
+        episode,
+        filename_template,
+    )?))
+}
+
+/// Before episodes had keys, audio was saved as `<podcast>/<title>.mp3` with
+/// both titles used verbatim. Titles that aren't a single plain file name
+/// are skipped, so a lookup or removal can't reach outside the podcast's
+/// directory.
+fn legacy_podcast_audio_path(podcast: &Podcast, episode: &Episode, path: &Path) -> Option<PathBuf> {
+    let is_plain_name = |name: &str| {
+        matches!(
+            Path::new(name).components().collect::<Vec<_>>()[..],
+            [std::path::Component::Normal(_)]
+        )
+    };
+    if episode.mime_type != "audio/mpeg"
+        || !is_plain_name(&podcast.title)
+        || !is_plain_name(&episode.title)
+    {
+        return None;
+    }
+
+    Some(
+        path.join(&podcast.title)
+            .join(&episode.title)
+            .with_extension("mp3"),
+    )
+}
+
+/// Also finds downloads saved under the default name or the legacy title
+/// name, so neither changing the template nor upgrading strands what's
+/// already cached.
+fn existing_podcast_audio_path(
+    podcast: &Podcast,
+    episode: &Episode,
+    path: &Path,
+    filename_template: &str,
+) -> Option<PathBuf> {
+    [filename_template, DEFAULT_FILENAME_TEMPLATE]
+        .into_iter()
+        .filter_map(|template| podcast_audio_path(podcast, episode, path, template).ok())
+        .chain(legacy_podcast_audio_path(podcast, episode, path))
+        .find(|audio_file| audio_file.exists())
+}
+
+/// Hosts often answer a dead enclosure with an HTML error page, which would
+/// otherwise be cached as audio and only fail once the decoder sees it.
+/// Servers frequently label audio as `application/octet-stream` or omit the
+/// header entirely, so only clearly textual types are rejected. Error
+/// statuses are rejected first, whatever body the server sent with them.
+fn check_audio_response(res: &reqwest::Response) -> Result<(), AnyError> {
+    res.error_for_status_ref()?;
+    let content_type = response_content_type(res);
+    let essence = content_type.split(';').next().unwrap_or_default().trim();
+    let is_text = essence.starts_with("text/")
+        || essence.ends_with("+xml")
+        || essence.ends_with("+json")
+        || matches!(essence, "application/json" | "application/xml");
+    if is_text {
+        return Err(format!("{} returned {essence} instead of audio", res.url()).into());
+    }
+    Ok(())
+}
+
+/// A strong `ETag`, or failing that `Last-Modified`, names the exact file a
+/// response carries, so a later `If-Range` only resumes that same file.
+fn response_validator(res: &reqwest::Response) -> Option<String> {
+    let header = |name| res.headers().get(name)?.to_str().ok();
+    header(reqwest::header::ETAG)
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| header(reqwest::header::LAST_MODIFIED))
+        .map(str::to_string)
+}
+
+fn content_range_start(res: &reqwest::Response) -> Option<u64> {
+    let content_range = res
+        .headers()
+        .get(reqwest::header::CONTENT_RANGE)?
+        .to_str()
+        .ok()?;
+    let (start, _) = content_range.strip_prefix("bytes ")?.split_once('-')?;
+    start.trim().parse().ok()
+}
+
+/// Appends to whatever `file` already holds when the server confirms with
+/// `If-Range` and `Content-Range` that the rest of the same audio follows, and
+/// starts over otherwise. The validator that `If-Range` needs is kept next to
+/// `file`, and a `file` without one is never resumed.
+async fn download_audio_to_file(
+    client: &reqwest::Client,
+    url: &str,
+    file: &Path,
+    on_progress: &mut impl FnMut(u64, Option<u64>),
+) -> Result<(), AnyError> {
+    let validator_file = suffixed_path(file, ".validator");
+    let validator = tokio::fs::read_to_string(&validator_file).await.ok();
+    let existing_len = tokio::fs::metadata(file)
+        .await
+        .map_or(0, |metadata| metadata.len());
+    let mut req = client.get(url);
+    if existing_len > 0
+        && let Some(validator) = &validator
+    {
+        req = req
+            .header(reqwest::header::RANGE, format!("bytes={existing_len}-"))
+            .header(reqwest::header::IF_RANGE, validator);
+    }
+    let mut res = send_with_retry(req).await?;
+    let is_resumed = existing_len > 0
+        && res.status() == reqwest::StatusCode::PARTIAL_CONTENT
+        && content_range_start(&res) == Some(existing_len);
+    if !is_resumed
+        && matches!(
+            res.status(),
+            reqwest::StatusCode::PARTIAL_CONTENT | reqwest::StatusCode::RANGE_NOT_SATISFIABLE
+        )
+    {
+        res = send_with_retry(client.get(url)).await?;
+    }
+    check_audio_response(&res)?;
 
-            Ok(audio_file)
+    let (mut writer, mut received) = if is_resumed {
+        let writer = tokio::fs::OpenOptions::new()
+            .append(true)
+            .open(file)
+            .await?;
+        (writer, existing_len)
+    } else {
+        match response_validator(&res) {
+            Some(validator) => tokio::fs::write(&validator_file, validator).await?,
+            None => _ = tokio::fs::remove_file(&validator_file).await,
         }
-        _ => Err("audio format not supported".into()),
+        (tokio::fs::File::create(file).await?, 0)
+    };
+    let total_len = res.content_length().map(|len| len + received);
+    while let Some(chunk) = res.chunk().await? {
+        writer.write_all(&chunk).await?;
+        received += chunk.len() as u64;
+        on_progress(received, total_len);
+    }
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Calls `on_progress` with the bytes received so far and the expected total
+/// as the audio downloads.
+pub async fn download_podcast_audio_to_path(
+    client: &reqwest::Client,
+    podcast: &Podcast,
+    episode: &Episode,
+    path: &Path,
+    filename_template: &str,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<PathBuf, AnyError> {
+    if let Some(audio_file) = existing_podcast_audio_path(podcast, episode, path, filename_template)
+    {
+        return Ok(audio_file);
+    }
+
+    let audio_file = podcast_audio_path(podcast, episode, path, filename_template)?;
+    if let Some(parent) = audio_file.parent() {
+        tokio::fs::create_dir_all(parent).await?;
     }
+    // A failed download keeps its `.part` file so the next attempt can resume.
+    let part_file = suffixed_path(&audio_file, ".part");
+    download_audio_to_file(client, &episode.url, &part_file, &mut on_progress).await?;
+    tokio::fs::rename(&part_file, &audio_file).await?;
+    _ = tokio::fs::remove_file(suffixed_path(&part_file, ".validator")).await;
+    Ok(audio_file)
+}
+
+/// Copies the episode's audio to `destination`, downloading it first if it
+/// isn't cached. A directory destination gets a file named after the episode.
+pub async fn export_podcast_audio_to_path(
+    client: &reqwest::Client,
+    podcast: &Podcast,
+    episode: &Episode,
+    path: &Path,
+    filename_template: &str,
+    destination: &Path,
+    on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<PathBuf, AnyError> {
+    let audio_file = download_podcast_audio_to_path(
+        client,
+        podcast,
+        episode,
+        path,
+        filename_template,
+        on_progress,
+    )
+    .await?;
+
+    let destination = if destination.is_dir() {
+        destination.join(podcast_audio_file_name(
+            podcast,
+            episode,
+            EXPORT_FILENAME_TEMPLATE,
+        )?)
+    } else {
+        destination.to_path_buf()
+    };
+    tokio::fs::copy(&audio_file, &destination).await?;
+    Ok(destination)
 }
 
-pub fn check_podcast_audio_in_path(podcast: &Podcast, episode: &Episode, path: &Path) -> bool {
-    path.join(&podcast.title)
-        .join(&episode.title)
-        .with_extension("mp3")
-        .exists()
+/// Streams write to their own temp file, leaving alone any `.part` file a
+/// failed download left to resume. Without `lock`, another download of the
+/// episode is running and the stream isn't saved at all.
+pub async fn stream_podcast_audio_to_path(
+    client: &reqwest::Client,
+    podcast: &Podcast,
+    episode: &Episode,
+    path: &Path,
+    filename_template: &str,
+    lock: Option<OwnedMutexGuard<()>>,
+) -> Result<(StreamReader, StreamHandle), AnyError> {
+    let audio_file = podcast_audio_path(podcast, episode, path, filename_template)?;
+    if let Some(parent) = audio_file.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let res = send_with_retry(client.get(&episode.url)).await?;
+    check_audio_response(&res)?;
+    let cache = lock.map(|lock| StreamCache {
+        temp_file: suffixed_path(&audio_file, ".stream"),
+        cache_file: audio_file,
+        lock,
+    });
+    Ok(stream_response(res, cache))
+}
+
+pub fn check_podcast_audio_in_path(
+    podcast: &Podcast,
+    episode: &Episode,
+    path: &Path,
+    filename_template: &str,
+) -> bool {
+    existing_podcast_audio_path(podcast, episode, path, filename_template).is_some()
+}
+
+pub async fn remove_podcast_audio_in_path(
+    podcast: &Podcast,
+    episode: &Episode,
+    path: &Path,
+    filename_template: &str,
+) -> Result<(), AnyError> {
+    if let Some(audio_file) = existing_podcast_audio_path(podcast, episode, path, filename_template)
+    {
+        tokio::fs::remove_file(audio_file).await?;
+    }
+    Ok(())
+}
+
+pub struct CachedAudio {
+    pub episode_index: usize,
+    pub len: u64,
+    pub modified: SystemTime,
+}
+
+pub fn cached_podcast_audio_in_path(
+    podcast: &Podcast,
+    path: &Path,
+    filename_template: &str,
+) -> Vec<CachedAudio> {
+    podcast
+        .episodes
+        .iter()
+        .enumerate()
+        .filter_map(|(episode_index, episode)| {
+            let audio_file =
+                existing_podcast_audio_path(podcast, episode, path, filename_template)?;
+            let metadata = audio_file.metadata().ok()?;
+            Some(CachedAudio {
+                episode_index,
+                len: metadata.len(),
+                modified: metadata.modified().ok()?,
+            })
+        })
+        .collect()
+}
+
+fn is_audio_file(file: &Path) -> bool {
+    file.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| AUDIO_EXTENSIONS.contains(&extension))
+}
+
+/// Walks the podcast directories rather than the episode lists, so podcasts
+/// whose episodes haven't been loaded yet are counted too. Filename templates
+/// can sort downloads into subdirectories, so the walk recurses, and a single
+/// podcast's directory can be sized the same way.
+pub fn audio_cache_size_in_path(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| {
+            let entry_path = entry.path();
+            match entry.file_type() {
+                Ok(file_type) if file_type.is_dir() => audio_cache_size_in_path(&entry_path),
+                Ok(_) if is_audio_file(&entry_path) => {
+                    entry.metadata().map_or(0, |metadata| metadata.len())
+                }
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_filename_replaces_separators_and_reserved_characters() {
+        assert_eq!(sanitize_filename("News 9/11 Special"), "News 9_11 Special");
+        assert_eq!(sanitize_filename("a:b"), "a_b");
+        assert_eq!(sanitize_filename("back\\slash"), "back_slash");
+    }
+
+    #[test]
+    fn sanitize_filename_never_returns_hidden_or_empty_names() {
+        assert_eq!(sanitize_filename(".hidden"), "hidden");
+        assert_eq!(sanitize_filename(".."), "_");
+        assert_eq!(sanitize_filename(""), "_");
+        assert_eq!(sanitize_filename("   "), "_");
+    }
+
+    #[test]
+    fn rss_channel_without_description_defaults_to_empty() {
+        let podcast = parse_podcast_info(
+            r#"<rss version="2.0"><channel>
+                <title>Show</title>
+                <item>
+                    <title>One</title>
+                    <description>Notes</description>
+                    <pubDate>Tue, 05 Mar 2024 10:00:00 +0000</pubDate>
+                    <enclosure url="https://example.com/1.mp3" type="audio/mpeg"/>
+                </item>
+            </channel></rss>"#,
+            "https://example.com/feed",
+        )
+        .unwrap();
+        assert_eq!(podcast.title, "Show");
+        assert_eq!(podcast.description, "");
+        assert_eq!(podcast.episodes.len(), 1);
+    }
+
+    #[test]
+    fn parse_pub_date_accepts_rfc_2822_and_rfc_3339() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 5);
+        assert_eq!(parse_pub_date("Tue, 05 Mar 2024 10:00:00 +0000"), date);
+        assert_eq!(parse_pub_date("Tue, 05 Mar 2024 10:00:00 EST"), date);
+        assert_eq!(parse_pub_date("2024-03-05T10:00:00Z"), date);
+        assert_eq!(parse_pub_date(" 2024-03-05 "), date);
+    }
+
+    #[test]
+    fn parse_pub_date_rejects_garbage() {
+        assert_eq!(parse_pub_date(""), None);
+        assert_eq!(parse_pub_date("sometime last week"), None);
+        assert_eq!(parse_pub_date("2024-13-45"), None);
+    }
+
+    #[test]
+    fn clean_description_strips_nested_tags() {
+        assert_eq!(
+            clean_description(
+                r#"<div><b>Big <a href="https://example.com">news</a></b> today</div>"#
+            ),
+            "Big news today"
+        );
+    }
+
+    #[test]
+    fn clean_description_keeps_line_and_paragraph_breaks() {
+        assert_eq!(
+            clean_description("<p>One</p><p>Two<br>Three<br/>Four</p>"),
+            "One\n\nTwo\nThree\nFour"
+        );
+    }
+
+    #[test]
+    fn decode_entities_handles_numeric_and_named_entities() {
+        assert_eq!(decode_entities("It&#8217;s &#x2019;24"), "It’s ’24");
+        assert_eq!(
+            decode_entities("&lt;b&gt; &amp; &quot;q&quot; &hellip;"),
+            "<b> & \"q\" …"
+        );
+    }
+
+    #[test]
+    fn decode_entities_leaves_stray_ampersands() {
+        assert_eq!(decode_entities("Q&A with AT&T"), "Q&A with AT&T");
+        assert_eq!(decode_entities("fish &amp chips"), "fish &amp chips");
+        assert_eq!(decode_entities("trailing &"), "trailing &");
+        assert_eq!(decode_entities("&unknown;"), "&unknown;");
+    }
+
+    #[test]
+    fn atom_entries_map_to_episodes() {
+        let podcast = parse_podcast_info(
+            r#"<feed xmlns="http://www.w3.org/2005/Atom">
+                <title>Atom Show</title>
+                <entry>
+                    <title>Episode 1</title>
+                    <id>urn:episode:1</id>
+                    <updated>2024-03-05T10:00:00Z</updated>
+                    <summary>Notes &amp; links</summary>
+                    <link rel="alternate" href="https://example.com/1"/>
+                    <link rel="enclosure" href="https://example.com/1.mp3" type="audio/mpeg"/>
+                </entry>
+                <entry>
+                    <title>No audio</title>
+                    <link rel="alternate" href="https://example.com/2"/>
+                </entry>
+            </feed>"#,
+            "https://example.com/atom",
+        )
+        .unwrap();
+        assert_eq!(podcast.title, "Atom Show");
+        assert_eq!(podcast.episodes.len(), 1);
+        let episode = &podcast.episodes[0];
+        assert_eq!(episode.title, "Episode 1");
+        assert_eq!(episode.url, "https://example.com/1.mp3");
+        assert_eq!(episode.pub_date, "2024-03-05");
+        assert_eq!(episode.description, "Notes & links");
+        assert_eq!(episode.guid.as_deref(), Some("urn:episode:1"));
+    }
+
+    #[test]
+    fn cdata_and_entity_encoded_titles_are_cleaned() {
+        let podcast = parse_podcast_info(
+            r#"<rss version="2.0"><channel>
+                <title>Tom &amp; Jerry</title>
+                <item>
+                    <title><![CDATA[Q&amp;A: <em>Rust</em> &#8217;24]]></title>
+                    <description><![CDATA[<p>First</p><p>Second</p>]]></description>
+                    <enclosure url="https://example.com/1.mp3" type="audio/mpeg"/>
+                </item>
+                <item>
+                    <title>Part<!-- draft --> two</title>
+                    <enclosure url="https://example.com/2.mp3" type="audio/mpeg"/>
+                </item>
+            </channel></rss>"#,
+            "https://example.com/feed",
+        )
+        .unwrap();
+        assert_eq!(podcast.title, "Tom & Jerry");
+        assert_eq!(podcast.episodes[0].title, "Q&A: Rust ’24");
+        assert_eq!(podcast.episodes[0].description, "First\n\nSecond");
+        assert_eq!(podcast.episodes[1].title, "Part two");
+    }
+
+    fn audio_response(status: u16, content_type: &str) -> reqwest::Response {
+        http::Response::builder()
+            .status(status)
+            .header(reqwest::header::CONTENT_TYPE, content_type)
+            .body("")
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn check_audio_response_rejects_html_pages() {
+        assert!(check_audio_response(&audio_response(200, "text/html; charset=utf-8")).is_err());
+    }
+
+    #[test]
+    fn check_audio_response_rejects_error_statuses() {
+        assert!(check_audio_response(&audio_response(404, "audio/mpeg")).is_err());
+    }
+
+    #[test]
+    fn check_audio_response_accepts_audio() {
+        assert!(check_audio_response(&audio_response(200, "audio/mpeg")).is_ok());
+    }
+
+    #[test]
+    fn content_range_start_reads_the_first_byte() {
+        let res: reqwest::Response = http::Response::builder()
+            .status(206)
+            .header(reqwest::header::CONTENT_RANGE, "bytes 100-199/200")
+            .body("")
+            .unwrap()
+            .into();
+        assert_eq!(content_range_start(&res), Some(100));
+        assert_eq!(
+            content_range_start(&audio_response(200, "audio/mpeg")),
+            None
+        );
+    }
+
+    #[test]
+    fn response_validator_prefers_strong_etags() {
+        let last_modified = "Tue, 05 Mar 2024 10:00:00 GMT";
+        let res: reqwest::Response = http::Response::builder()
+            .header(reqwest::header::ETAG, "\"abc\"")
+            .header(reqwest::header::LAST_MODIFIED, last_modified)
+            .body("")
+            .unwrap()
+            .into();
+        assert_eq!(response_validator(&res).as_deref(), Some("\"abc\""));
+
+        let res: reqwest::Response = http::Response::builder()
+            .header(reqwest::header::ETAG, "W/\"abc\"")
+            .header(reqwest::header::LAST_MODIFIED, last_modified)
+            .body("")
+            .unwrap()
+            .into();
+        assert_eq!(response_validator(&res).as_deref(), Some(last_modified));
+    }
+
+    #[test]
+    fn legacy_audio_path_keeps_verbatim_titles_inside_the_podcast() {
+        let podcast = parse_podcast_info(
+            r#"<rss version="2.0"><channel>
+                <title>Show</title>
+                <item>
+                    <title>Ep. 1: Intro</title>
+                    <enclosure url="https://example.com/1.mp3" type="audio/mpeg"/>
+                </item>
+                <item>
+                    <title>../escape</title>
+                    <enclosure url="https://example.com/2.mp3" type="audio/mpeg"/>
+                </item>
+            </channel></rss>"#,
+            "https://example.com/feed",
+        )
+        .unwrap();
+        let path = Path::new("data");
+        // The old code swapped the extension, so anything after a dot was lost.
+        assert_eq!(
+            legacy_podcast_audio_path(&podcast, &podcast.episodes[0], path),
+            Some(PathBuf::from("data/Show/Ep.mp3"))
+        );
+        assert_eq!(
+            legacy_podcast_audio_path(&podcast, &podcast.episodes[1], path),
+            None
+        );
+    }
+
+    #[test]
+    fn escaped_text_in_plain_titles_is_kept() {
+        let podcast = parse_podcast_info(
+            r#"<rss version="2.0"><channel>
+                <title>Show</title>
+                <item>
+                    <title>Vectors &lt;T&gt; in Rust, &lt;b&gt; and &amp;amp;</title>
+                    <description>&lt;p&gt;Escaped &lt;em&gt;HTML&lt;/em&gt;&lt;/p&gt;</description>
+                    <enclosure url="https://example.com/1.mp3" type="audio/mpeg"/>
+                </item>
+            </channel></rss>"#,
+            "https://example.com/feed",
+        )
+        .unwrap();
+        assert_eq!(
+            podcast.episodes[0].title,
+            "Vectors <T> in Rust, <b> and &amp;"
+        );
+        assert_eq!(podcast.episodes[0].description, "Escaped HTML");
+    }
+
+    #[test]
+    fn merge_keeps_only_dropped_episodes_with_state() {
+        let feed = |items: &[&str]| {
+            let items = items
+                .iter()
+                .map(|guid| {
+                    format!(
+                        r#"<item><title>{guid}</title><guid>{guid}</guid>
+                        <enclosure url="https://example.com/{guid}.mp3" type="audio/mpeg"/></item>"#
+                    )
+                })
+                .collect::<String>();
+            parse_podcast_info(
+                &format!(
+                    r#"<rss version="2.0"><channel><title>Show</title>{items}</channel></rss>"#
+                ),
+                "https://example.com/feed",
+            )
+            .unwrap()
+        };
+        let mut old_podcast = feed(&["a", "b", "c"]);
+        old_podcast.episodes[0].played = true;
+        old_podcast.episodes[2].position_secs = Some(30);
+        let mut podcast = feed(&["c", "d"]);
+
+        let new_episodes = merge_episode_state(
+            &mut podcast,
+            &old_podcast,
+            Path::new("missing"),
+            DEFAULT_FILENAME_TEMPLATE,
+        );
+        assert_eq!(new_episodes, 1);
+        let keys = podcast
+            .episodes
+            .iter()
+            .map(Episode::key)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, ["c", "d", "a"]);
+        assert_eq!(podcast.episodes[0].position_secs, Some(30));
+    }
 }
@@ -0,0 +1,61 @@
+use serde::Deserialize;
+
+use crate::AnyError;
+
+const ITUNES_SEARCH_URL: &str = "https://itunes.apple.com/search";
+const SEARCH_LIMIT: &str = "25";
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub title: String,
+    pub author: String,
+    pub feed_url: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItunesSearchResponse {
+    results: Vec<ItunesSearchResult>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItunesSearchResult {
+    collection_name: Option<String>,
+    artist_name: Option<String>,
+    feed_url: Option<String>,
+}
+
+pub async fn search_podcasts(
+    client: &reqwest::Client,
+    term: &str,
+) -> Result<Vec<SearchResult>, AnyError> {
+    let url = reqwest::Url::parse_with_params(
+        ITUNES_SEARCH_URL,
+        &[
+            ("media", "podcast"),
+            ("limit", SEARCH_LIMIT),
+            ("term", term),
+        ],
+    )?;
+    let text = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let response: ItunesSearchResponse = serde_json::from_str(&text)?;
+
+    Ok(response
+        .results
+        .into_iter()
+        .filter_map(|result| {
+            Some(SearchResult {
+                title: result.collection_name?,
+                author: result.artist_name.unwrap_or_default(),
+                feed_url: result.feed_url?,
+            })
+        })
+        .collect())
+}
@@ -0,0 +1,31 @@
+use std::path::Path;
+
+use crate::AnyError;
+use serde::{Deserialize, Serialize};
+
+/// Where the user left off, keyed by feed url and episode key rather than list
+/// indices so added or reordered podcasts don't shift the restored selection.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Session {
+    pub podcast_url: Option<String>,
+    pub episode_key: Option<String>,
+}
+
+pub const SESSION_FILE: &str = "session.json";
+
+pub async fn load_session_from_path(path: &Path) -> Result<Session, AnyError> {
+    let session_file = path.join(SESSION_FILE);
+    if !session_file.exists() {
+        return Ok(Session::default());
+    }
+
+    let json = tokio::fs::read_to_string(session_file).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub async fn save_session_to_path(session: &Session, path: &Path) -> Result<(), AnyError> {
+    let json = serde_json::to_string(session)?;
+    tokio::fs::write(path.join(SESSION_FILE), json).await?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+use std::{collections::BTreeMap, path::Path, time::Duration};
+
+use crate::AnyError;
+use crate::podcast::Podcast;
+use serde::{Deserialize, Serialize};
+
+/// Accumulated as episodes finish or are marked played. Kept separately from
+/// the feeds so the history survives unsubscribing, and written as plain JSON
+/// so other tools can read it.
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct ListeningStats {
+    pub episodes_played: u64,
+    pub listening_secs: u64,
+    /// Keyed by feed url.
+    pub podcasts: BTreeMap<String, PodcastStats>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct PodcastStats {
+    pub title: String,
+    pub episodes_played: u64,
+    pub listening_secs: u64,
+}
+
+impl ListeningStats {
+    pub fn record_played(&mut self, podcast: &Podcast, listened: Duration) {
+        self.episodes_played += 1;
+        self.listening_secs += listened.as_secs();
+
+        let podcast_stats = self.podcasts.entry(podcast.url.clone()).or_default();
+        podcast_stats.title.clone_from(&podcast.title);
+        podcast_stats.episodes_played += 1;
+        podcast_stats.listening_secs += listened.as_secs();
+    }
+}
+
+pub const STATS_FILE: &str = "stats.json";
+
+pub async fn load_stats_from_path(path: &Path) -> Result<ListeningStats, AnyError> {
+    let stats_file = path.join(STATS_FILE);
+    if !stats_file.exists() {
+        return Ok(ListeningStats::default());
+    }
+
+    let json = tokio::fs::read_to_string(stats_file).await?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+pub async fn save_stats_to_path(stats: &ListeningStats, path: &Path) -> Result<(), AnyError> {
+    let json = serde_json::to_string_pretty(stats)?;
+    tokio::fs::write(path.join(STATS_FILE), json).await?;
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    path::PathBuf,
+    sync::{Arc, Condvar, Mutex},
+};
+
+use tokio::{io::AsyncWriteExt, sync::OwnedMutexGuard};
+
+use crate::AnyError;
+
+#[derive(Default)]
+struct StreamBuffer {
+    data: Vec<u8>,
+    total_len: Option<u64>,
+    finished: bool,
+    error: Option<String>,
+    waiting: bool,
+}
+
+type SharedStreamBuffer = Arc<(Mutex<StreamBuffer>, Condvar)>;
+
+/// Reads audio bytes as they arrive from the network, blocking until the
+/// requested range has been downloaded.
+pub struct StreamReader {
+    shared: SharedStreamBuffer,
+    position: u64,
+}
+
+/// Lets the UI observe a stream while its reader is owned by the decoder.
+#[derive(Clone)]
+pub struct StreamHandle {
+    shared: SharedStreamBuffer,
+}
+
+impl StreamHandle {
+    pub fn is_buffering(&self) -> bool {
+        let buffer = self.shared.0.lock().unwrap();
+        buffer.waiting && !buffer.finished
+    }
+}
+
+impl StreamReader {
+    pub fn total_len(&self) -> Option<u64> {
+        self.shared.0.lock().unwrap().total_len
+    }
+}
+
+impl Read for StreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let (lock, condvar) = &*self.shared;
+        let mut buffer = lock.lock().unwrap();
+        loop {
+            if let Some(error) = &buffer.error {
+                return Err(io::Error::other(error.clone()));
+            }
+
+            if self.position < buffer.data.len() as u64 {
+                let start = self.position as usize;
+                let len = buf.len().min(buffer.data.len() - start);
+                buf[..len].copy_from_slice(&buffer.data[start..start + len]);
+                self.position += len as u64;
+                buffer.waiting = false;
+                return Ok(len);
+            }
+
+            if buffer.finished {
+                buffer.waiting = false;
+                return Ok(0);
+            }
+
+            buffer.waiting = true;
+            buffer = condvar.wait(buffer).unwrap();
+        }
+    }
+}
+
+impl Seek for StreamReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::Current(offset) => self.position as i64 + offset,
+            SeekFrom::End(offset) => {
+                let buffer = self.shared.0.lock().unwrap();
+                let len = buffer
+                    .total_len
+                    .or(buffer.finished.then_some(buffer.data.len() as u64))
+                    .ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::Unsupported, "stream length is unknown")
+                    })?;
+                len as i64 + offset
+            }
+        };
+        if position < 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before start of stream",
+            ));
+        }
+
+        self.position = position as u64;
+        Ok(self.position)
+    }
+}
+
+/// Where a stream saves what it downloads. `lock` keeps other writers of the
+/// same episode's audio waiting until the stream is done with `temp_file`.
+pub struct StreamCache {
+    pub temp_file: PathBuf,
+    pub cache_file: PathBuf,
+    pub lock: OwnedMutexGuard<()>,
+}
+
+/// Starts downloading `res` in the background. With a `cache`, the body is
+/// also written to its `temp_file`, which is renamed to `cache_file` once the
+/// whole body has arrived.
+pub fn stream_response(
+    mut res: reqwest::Response,
+    cache: Option<StreamCache>,
+) -> (StreamReader, StreamHandle) {
+    let shared = SharedStreamBuffer::default();
+    shared.0.lock().unwrap().total_len = res.content_length();
+
+    let task_shared = shared.clone();
+    tokio::spawn(async move {
+        let mut file = match &cache {
+            Some(cache) => tokio::fs::File::create(&cache.temp_file).await.ok(),
+            None => None,
+        };
+        let result: Result<(), AnyError> = async {
+            while let Some(chunk) = res.chunk().await? {
+                if let Some(writer) = &mut file
+                    && writer.write_all(&chunk).await.is_err()
+                {
+                    file = None;
+                }
+
+                let (lock, condvar) = &*task_shared;
+                lock.lock().unwrap().data.extend_from_slice(&chunk);
+                condvar.notify_all();
+            }
+            Ok(())
+        }
+        .await;
+
+        let is_ok = result.is_ok();
+        {
+            let (lock, condvar) = &*task_shared;
+            let mut buffer = lock.lock().unwrap();
+            match result {
+                Ok(()) => buffer.finished = true,
+                Err(err) => buffer.error = Some(err.to_string()),
+            }
+            condvar.notify_all();
+        }
+
+        let is_cached = match &mut file {
+            Some(writer) => is_ok && writer.flush().await.is_ok(),
+            None => false,
+        };
+        drop(file);
+        if let Some(StreamCache {
+            temp_file,
+            cache_file,
+            lock,
+        }) = cache
+        {
+            if is_cached {
+                _ = tokio::fs::rename(&temp_file, &cache_file).await;
+            } else {
+                _ = tokio::fs::remove_file(&temp_file).await;
+            }
+            drop(lock);
+        }
+    });
+
+    (
+        StreamReader {
+            shared: shared.clone(),
+            position: 0,
+        },
+        StreamHandle { shared },
+    )
+}
@@ -0,0 +1,61 @@
+use ratatui::style::{Color, Style, Stylize};
+
+pub const THEME_NAMES: [&str; 3] = ["default", "monochrome", "high-contrast"];
+
+#[derive(Clone, Copy)]
+pub struct Theme {
+    pub title: Style,
+    pub table_header: Style,
+    pub highlight: Style,
+    pub border: Style,
+    pub played: Style,
+    pub now_playing: Style,
+    pub gauge: Style,
+    pub error_border: Style,
+}
+
+impl Theme {
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "monochrome" => Self {
+                now_playing: Style::new().bold(),
+                error_border: Style::new().bold(),
+                ..Self::default()
+            },
+            "high-contrast" => Self {
+                title: Style::new().bold().fg(Color::Yellow),
+                table_header: Style::new().bold().underlined().fg(Color::White),
+                highlight: Style::new().bold().fg(Color::Black).bg(Color::Yellow),
+                border: Style::new().bold().fg(Color::White),
+                played: Style::new().fg(Color::Gray),
+                now_playing: Style::new().bold().fg(Color::LightGreen),
+                gauge: Style::new().bold().fg(Color::Yellow),
+                error_border: Style::new().bold().fg(Color::LightRed),
+            },
+            _ => Self::default(),
+        }
+    }
+
+    pub fn next_name(name: &str) -> &'static str {
+        let index = THEME_NAMES
+            .iter()
+            .position(|theme_name| *theme_name == name)
+            .map_or(0, |index| (index + 1) % THEME_NAMES.len());
+        THEME_NAMES[index]
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            title: Style::new().bold(),
+            table_header: Style::new().underlined(),
+            highlight: Style::new().reversed(),
+            border: Style::new(),
+            played: Style::new().dim(),
+            now_playing: Style::new().green(),
+            gauge: Style::new().bold(),
+            error_border: Style::new().red(),
+        }
+    }
+}
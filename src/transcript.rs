@@ -0,0 +1,122 @@
+use crate::podcast::{clean_description, decode_entities};
+
+/// Paragraphs of untimed cues are broken at the first sentence end past this
+/// many characters, since caption cues rarely line up with sentences.
+const PARAGRAPH_LEN: usize = 500;
+
+struct Cue {
+    start: String,
+    speaker: Option<String>,
+    text: String,
+}
+
+/// Whether the transcript has cue timings that can be shown or hidden.
+pub fn has_timestamps(mime_type: &str) -> bool {
+    matches!(
+        mime_type,
+        "text/vtt" | "application/srt" | "application/x-subrip" | "text/srt"
+    )
+}
+
+/// Cuts `00:01:02.500` or `00:01:02,500` down to `00:01:02`.
+fn short_timestamp(timestamp: &str) -> String {
+    timestamp
+        .split(['.', ','])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Drops markup such as `<i>` from a cue line, remembering the speaker of a
+/// WebVTT `<v Speaker>` voice tag.
+fn strip_cue_tags(line: &str, speaker: &mut Option<String>) -> String {
+    let mut text = String::new();
+    let mut rest = line;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            rest = &rest[start..];
+            break;
+        };
+        let tag = &rest[start + 1..start + end];
+        if let Some(name) = tag.strip_prefix('v').and_then(|tag| {
+            tag.trim_start_matches(|c: char| c == '.' || c.is_alphanumeric())
+                .strip_prefix(' ')
+        }) {
+            *speaker = Some(name.trim().to_string());
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    decode_entities(text.trim())
+}
+
+/// Splits WebVTT or SRT text into cues. Blocks without a timing line, like the
+/// `WEBVTT` header and `NOTE` comments, are skipped.
+fn parse_cues(text: &str) -> Vec<Cue> {
+    text.replace("\r\n", "\n")
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut lines = block.lines().skip_while(|line| !line.contains("-->"));
+            let start = lines.next()?.split("-->").next()?.trim();
+            let mut speaker = None;
+            let text = lines
+                .map(|line| strip_cue_tags(line, &mut speaker))
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ");
+            (!text.is_empty()).then(|| Cue {
+                start: short_timestamp(start),
+                speaker,
+                text,
+            })
+        })
+        .collect()
+}
+
+fn format_cues(text: &str, with_timestamps: bool) -> String {
+    let cues = parse_cues(text);
+    if with_timestamps {
+        return cues
+            .iter()
+            .map(|cue| match &cue.speaker {
+                Some(speaker) => format!("[{}] {speaker}: {}", cue.start, cue.text),
+                None => format!("[{}] {}", cue.start, cue.text),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    let mut paragraphs = Vec::<String>::new();
+    let mut current_speaker = None;
+    for cue in &cues {
+        let is_new_speaker = cue.speaker.is_some() && cue.speaker != current_speaker;
+        let is_long = paragraphs.last().is_none_or(|paragraph| {
+            paragraph.len() >= PARAGRAPH_LEN && paragraph.ends_with(['.', '?', '!'])
+        });
+        if is_new_speaker || is_long {
+            current_speaker = cue.speaker.clone().or(current_speaker);
+            paragraphs.push(match &cue.speaker {
+                Some(speaker) if is_new_speaker => format!("{speaker}: {}", cue.text),
+                _ => cue.text.clone(),
+            });
+        } else if let Some(paragraph) = paragraphs.last_mut() {
+            paragraph.push(' ');
+            paragraph.push_str(&cue.text);
+        }
+    }
+    paragraphs.join("\n\n")
+}
+
+/// Turns a downloaded transcript into readable text. WebVTT and SRT cues are
+/// joined into paragraphs, or listed with their start times when
+/// `with_timestamps` is set.
+pub fn format_transcript(text: &str, mime_type: &str, with_timestamps: bool) -> String {
+    if has_timestamps(mime_type) {
+        format_cues(text, with_timestamps)
+    } else if mime_type == "text/html" {
+        clean_description(text)
+    } else {
+        text.trim().to_string()
+    }
+}
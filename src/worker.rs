@@ -0,0 +1,427 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rodio::Source;
+use tokio::sync::{
+    OwnedMutexGuard, Semaphore,
+    mpsc::{UnboundedReceiver, UnboundedSender, unbounded_channel},
+};
+
+use crate::AnyError;
+use crate::credentials::FeedCredentials;
+use crate::http::describe_http_error;
+use crate::podcast::{
+    Chapter, Episode, FeedValidators, Podcast, check_podcast_audio_in_path,
+    download_chapters_from_url, download_podcast_artwork_to_path, download_podcast_audio_to_path,
+    download_podcast_info_from_url, download_podcast_transcript_to_path,
+    export_podcast_audio_to_path, fetch_podcast_audio_size, stream_podcast_audio_to_path,
+};
+use crate::search::{SearchResult, search_podcasts};
+use crate::stream::StreamHandle;
+
+const MAX_CONCURRENT_FEEDS: usize = 4;
+const PROGRESS_INTERVAL: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedRequestKind {
+    Add,
+    Import,
+    Update,
+    AutoUpdate,
+}
+
+#[derive(Debug)]
+pub enum WorkerRequest {
+    FetchFeed {
+        url: String,
+        kind: FeedRequestKind,
+        validators: FeedValidators,
+        credentials: Option<FeedCredentials>,
+    },
+    LoadEpisode {
+        podcast: Box<Podcast>,
+        episode: Box<Episode>,
+        stream_audio: bool,
+    },
+    DownloadEpisode {
+        podcast: Box<Podcast>,
+        episode: Box<Episode>,
+    },
+    SearchPodcasts {
+        term: String,
+    },
+    FetchChapters {
+        episode_url: String,
+        chapters_url: String,
+    },
+    FetchTranscript {
+        podcast: Box<Podcast>,
+        episode: Box<Episode>,
+    },
+    ExportEpisode {
+        podcast: Box<Podcast>,
+        episode: Box<Episode>,
+        destination: PathBuf,
+    },
+    FetchEpisodeSize {
+        episode: Box<Episode>,
+    },
+}
+
+pub struct EpisodeAudio {
+    pub source: Box<dyn Source + Send>,
+    pub total_duration: Option<Duration>,
+    pub stream: Option<StreamHandle>,
+}
+
+pub enum WorkerEvent {
+    FeedFetched {
+        url: String,
+        kind: FeedRequestKind,
+        result: Result<Option<Podcast>, AnyError>,
+    },
+    EpisodeLoaded {
+        episode_url: String,
+        result: Result<EpisodeAudio, AnyError>,
+    },
+    DownloadProgress {
+        episode_url: String,
+        received: u64,
+        total_len: Option<u64>,
+    },
+    EpisodeDownloaded {
+        episode_url: String,
+        title: String,
+        result: Result<(), AnyError>,
+    },
+    SearchFinished {
+        term: String,
+        result: Result<Vec<SearchResult>, AnyError>,
+    },
+    ChaptersFetched {
+        episode_url: String,
+        result: Result<Vec<Chapter>, AnyError>,
+    },
+    TranscriptFetched {
+        episode_url: String,
+        result: Result<String, AnyError>,
+    },
+    EpisodeExported {
+        episode_url: String,
+        title: String,
+        result: Result<PathBuf, AnyError>,
+    },
+    EpisodeSizeFetched {
+        episode_url: String,
+        result: Result<Option<u64>, AnyError>,
+    },
+    ArtworkFetched {
+        title: String,
+        result: Result<(), AnyError>,
+    },
+}
+
+/// One lock per episode url with audio being written to the cache. A second
+/// download, export or load of the same episode waits for the first and then
+/// finds the audio cached, instead of writing to the same `.part` file.
+#[derive(Clone, Default)]
+struct AudioLocks(Arc<Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>>);
+
+impl AudioLocks {
+    fn get(&self, episode_url: &str) -> Arc<tokio::sync::Mutex<()>> {
+        let mut locks = self.0.lock().unwrap();
+        // Only the map holds locks that nobody is writing with or waiting on.
+        locks.retain(|_, lock| Arc::strong_count(lock) > 1);
+        locks.entry(episode_url.to_string()).or_default().clone()
+    }
+
+    async fn lock(&self, episode_url: &str) -> OwnedMutexGuard<()> {
+        self.get(episode_url).lock_owned().await
+    }
+
+    fn try_lock(&self, episode_url: &str) -> Option<OwnedMutexGuard<()>> {
+        self.get(episode_url).try_lock_owned().ok()
+    }
+}
+
+/// Forwards download progress to the UI, at most once per `PROGRESS_INTERVAL`
+/// bytes so large files don't flood the event channel.
+fn report_progress(
+    event_tx: &UnboundedSender<WorkerEvent>,
+    episode_url: &str,
+) -> impl FnMut(u64, Option<u64>) {
+    let event_tx = event_tx.clone();
+    let episode_url = episode_url.to_string();
+    let mut last_reported = 0;
+    move |received, total_len| {
+        if received - last_reported >= PROGRESS_INTERVAL || Some(received) == total_len {
+            last_reported = received;
+            _ = event_tx.send(WorkerEvent::DownloadProgress {
+                episode_url: episode_url.clone(),
+                received,
+                total_len,
+            });
+        }
+    }
+}
+
+async fn stream_episode_audio(
+    client: &reqwest::Client,
+    podcast: &Podcast,
+    episode: &Episode,
+    data_path: &Path,
+    filename_template: &str,
+    lock: Option<OwnedMutexGuard<()>>,
+) -> Result<EpisodeAudio, AnyError> {
+    let (reader, handle) =
+        stream_podcast_audio_to_path(client, podcast, episode, data_path, filename_template, lock)
+            .await?;
+    let mime_type = episode.mime_type.clone();
+    let source = tokio::task::spawn_blocking(move || {
+        let mut builder = rodio::Decoder::builder();
+        if let Some(byte_len) = reader.total_len() {
+            builder = builder.with_byte_len(byte_len);
+        }
+        builder
+            .with_data(reader)
+            .with_seekable(true)
+            .with_mime_type(&mime_type)
+            .build()
+    })
+    .await??;
+
+    Ok(EpisodeAudio {
+        total_duration: source.total_duration(),
+        source: Box::new(source),
+        stream: Some(handle),
+    })
+}
+
+async fn open_episode_audio(
+    client: &reqwest::Client,
+    event_tx: &UnboundedSender<WorkerEvent>,
+    podcast: &Podcast,
+    episode: &Episode,
+    data_path: &Path,
+    filename_template: &str,
+) -> Result<EpisodeAudio, AnyError> {
+    let audio_file = download_podcast_audio_to_path(
+        client,
+        podcast,
+        episode,
+        data_path,
+        filename_template,
+        report_progress(event_tx, &episode.url),
+    )
+    .await?;
+    let file = File::open(audio_file)?;
+    let byte_len = file.metadata()?.len();
+    let source = rodio::Decoder::builder()
+        .with_data(BufReader::new(file))
+        .with_byte_len(byte_len)
+        .with_seekable(true)
+        .with_mime_type(&episode.mime_type)
+        .build()?;
+
+    Ok(EpisodeAudio {
+        total_duration: source.total_duration(),
+        source: Box::new(source),
+        stream: None,
+    })
+}
+
+async fn handle_request(
+    request: WorkerRequest,
+    client: &reqwest::Client,
+    event_tx: &UnboundedSender<WorkerEvent>,
+    audio_locks: &AudioLocks,
+    data_path: &Path,
+    filename_template: &str,
+    proxy: Option<&str>,
+) -> WorkerEvent {
+    match request {
+        WorkerRequest::FetchFeed {
+            url,
+            kind,
+            validators,
+            credentials,
+        } => {
+            let result =
+                download_podcast_info_from_url(client, &url, &validators, credentials.as_ref())
+                    .await
+                    .map_err(|err| describe_http_error(err, "feed", proxy));
+            if let Ok(Some(podcast)) = &result {
+                _ = event_tx.send(WorkerEvent::ArtworkFetched {
+                    title: podcast.title.clone(),
+                    result: download_podcast_artwork_to_path(client, podcast, data_path)
+                        .await
+                        .map_err(|err| describe_http_error(err, "artwork", proxy)),
+                });
+            }
+            WorkerEvent::FeedFetched { url, kind, result }
+        }
+        WorkerRequest::LoadEpisode {
+            podcast,
+            episode,
+            stream_audio,
+        } => {
+            let result = if stream_audio
+                && !check_podcast_audio_in_path(&podcast, &episode, data_path, filename_template)
+            {
+                // A stream can't wait for another download of the episode to
+                // finish, so it plays without saving instead.
+                let lock = audio_locks.try_lock(&episode.url);
+                stream_episode_audio(
+                    client,
+                    &podcast,
+                    &episode,
+                    data_path,
+                    filename_template,
+                    lock,
+                )
+                .await
+            } else {
+                let _lock = audio_locks.lock(&episode.url).await;
+                open_episode_audio(
+                    client,
+                    event_tx,
+                    &podcast,
+                    &episode,
+                    data_path,
+                    filename_template,
+                )
+                .await
+            };
+            WorkerEvent::EpisodeLoaded {
+                result: result.map_err(|err| describe_http_error(err, "episode", proxy)),
+                episode_url: episode.url,
+            }
+        }
+        WorkerRequest::DownloadEpisode { podcast, episode } => {
+            let _lock = audio_locks.lock(&episode.url).await;
+            WorkerEvent::EpisodeDownloaded {
+                result: download_podcast_audio_to_path(
+                    client,
+                    &podcast,
+                    &episode,
+                    data_path,
+                    filename_template,
+                    report_progress(event_tx, &episode.url),
+                )
+                .await
+                .map(|_| ())
+                .map_err(|err| describe_http_error(err, "download", proxy)),
+                episode_url: episode.url,
+                title: episode.title,
+            }
+        }
+        WorkerRequest::SearchPodcasts { term } => WorkerEvent::SearchFinished {
+            result: search_podcasts(client, &term)
+                .await
+                .map_err(|err| describe_http_error(err, "search", proxy)),
+            term,
+        },
+        WorkerRequest::FetchChapters {
+            episode_url,
+            chapters_url,
+        } => WorkerEvent::ChaptersFetched {
+            result: download_chapters_from_url(client, &chapters_url)
+                .await
+                .map_err(|err| describe_http_error(err, "chapters", proxy)),
+            episode_url,
+        },
+        WorkerRequest::FetchTranscript { podcast, episode } => WorkerEvent::TranscriptFetched {
+            result: download_podcast_transcript_to_path(client, &podcast, &episode, data_path)
+                .await
+                .map_err(|err| describe_http_error(err, "transcript", proxy)),
+            episode_url: episode.url,
+        },
+        WorkerRequest::ExportEpisode {
+            podcast,
+            episode,
+            destination,
+        } => {
+            let _lock = audio_locks.lock(&episode.url).await;
+            WorkerEvent::EpisodeExported {
+                result: export_podcast_audio_to_path(
+                    client,
+                    &podcast,
+                    &episode,
+                    data_path,
+                    filename_template,
+                    &destination,
+                    report_progress(event_tx, &episode.url),
+                )
+                .await
+                .map_err(|err| describe_http_error(err, "download", proxy)),
+                episode_url: episode.url,
+                title: episode.title,
+            }
+        }
+        WorkerRequest::FetchEpisodeSize { episode } => WorkerEvent::EpisodeSizeFetched {
+            result: fetch_podcast_audio_size(client, &episode)
+                .await
+                .map_err(|err| describe_http_error(err, "episode", proxy)),
+            episode_url: episode.url,
+        },
+    }
+}
+
+pub fn spawn_worker(
+    data_path: PathBuf,
+    filename_template: String,
+    proxy: Option<String>,
+    client: reqwest::Client,
+) -> (
+    UnboundedSender<WorkerRequest>,
+    UnboundedReceiver<WorkerEvent>,
+) {
+    let (request_tx, mut request_rx) = unbounded_channel::<WorkerRequest>();
+    let (event_tx, event_rx) = unbounded_channel();
+
+    tokio::spawn(async move {
+        let feed_semaphore = Arc::new(Semaphore::new(MAX_CONCURRENT_FEEDS));
+        let audio_locks = AudioLocks::default();
+        while let Some(request) = request_rx.recv().await {
+            let event_tx = event_tx.clone();
+            let data_path = data_path.clone();
+            let filename_template = filename_template.clone();
+            let proxy = proxy.clone();
+            let client = client.clone();
+            let feed_semaphore = feed_semaphore.clone();
+            let audio_locks = audio_locks.clone();
+            tokio::spawn(async move {
+                let _permit = match request {
+                    WorkerRequest::FetchFeed { .. } => feed_semaphore.acquire_owned().await.ok(),
+                    WorkerRequest::LoadEpisode { .. }
+                    | WorkerRequest::DownloadEpisode { .. }
+                    | WorkerRequest::SearchPodcasts { .. }
+                    | WorkerRequest::FetchChapters { .. }
+                    | WorkerRequest::FetchTranscript { .. }
+                    | WorkerRequest::ExportEpisode { .. }
+                    | WorkerRequest::FetchEpisodeSize { .. } => None,
+                };
+                _ = event_tx.send(
+                    handle_request(
+                        request,
+                        &client,
+                        &event_tx,
+                        &audio_locks,
+                        &data_path,
+                        &filename_template,
+                        proxy.as_deref(),
+                    )
+                    .await,
+                );
+            });
+        }
+    });
+
+    (request_tx, event_rx)
+}